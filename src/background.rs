@@ -0,0 +1,33 @@
+use crate::color::Color;
+use crate::utils::{fPI, Vec3};
+
+mod solid;
+mod gradient;
+mod environment;
+
+/// Radiance arriving from infinitely far away along rays that escape the
+/// scene.
+pub trait Background: Send + Sync {
+    fn value(&self, direction: Vec3) -> Color;
+
+    /// Picks a direction towards the background, returning it with its
+    /// density over solid angle. The default samples the sphere uniformly.
+    fn sample(&self) -> (Vec3, f64) {
+        (Vec3::random_unit_vector(), 1.0 / (4.0 * fPI))
+    }
+
+    /// Density over solid angle with which `sample` returns `direction`.
+    fn pdf(&self, _direction: Vec3) -> f64 {
+        1.0 / (4.0 * fPI)
+    }
+
+    /// Whether `sample` follows the background's brightness closely enough
+    /// to be worth using for direct lighting.
+    fn importance_sampled(&self) -> bool {
+        false
+    }
+}
+
+pub use solid::SolidBackground;
+pub use gradient::GradientBackground;
+pub use environment::EnvironmentMap;
@@ -0,0 +1,147 @@
+use std::path::Path;
+use std::sync::Arc;
+use image::{DynamicImage, ImageResult};
+use crate::background::Background;
+use crate::color::{gamma_to_linear, luminance, Color};
+use crate::utils::{degrees_to_radians, fPI, rand_f64, Distribution2D, Vec3};
+
+/// Equirectangular image of the surroundings, with +y up and the middle
+/// column facing +x. Horizontally this matches `Sphere`'s u, but rows run from
+/// the top of the image down, where `Sphere`'s v rises from the south pole.
+/// The map can be turned about the y axis and scaled in brightness, and is
+/// importance sampled in proportion to texel luminance.
+#[derive(Clone)]
+pub struct EnvironmentMap {
+    width: u32,
+    height: u32,
+    texels: Vec<Color>,
+    rotation: f64,
+    intensity: f64,
+    distribution: Distribution2D,
+}
+
+impl EnvironmentMap {
+    /// Loads an .hdr, .exr or any other format the `image` crate reads.
+    /// Floating-point images are taken as linear; 8 and 16-bit ones are
+    /// decoded from display gamma.
+    pub fn load(path: impl AsRef<Path>) -> ImageResult<Arc<Self>> {
+        Self::load_with(path, 0.0, 1.0)
+    }
+
+    /// Like `load`, turned by `rotation` degrees about +y and scaled by
+    /// `intensity`.
+    pub fn load_with(path: impl AsRef<Path>, rotation: f64, intensity: f64) -> ImageResult<Arc<Self>> {
+        let image = image::open(path)?;
+        let linear = matches!(image, DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_));
+        let image = image.into_rgb32f();
+        let (width, height) = image.dimensions();
+        let decode = |c: f32| if linear { c as f64 } else { gamma_to_linear(c as f64) };
+        let texels = image
+            .pixels()
+            .map(|pixel| Color::from(decode(pixel[0]), decode(pixel[1]), decode(pixel[2])))
+            .collect();
+        Ok(Self::from_texels(width, height, texels, rotation, intensity))
+    }
+
+    /// A map over linear `texels`, stored row by row from the top.
+    pub fn from_texels(width: u32, height: u32, texels: Vec<Color>, rotation: f64, intensity: f64) -> Arc<Self> {
+        assert!(width > 0 && height > 0, "environment map must have at least one texel");
+        assert_eq!(texels.len(), width as usize * height as usize, "texel count must match the image size");
+        // Rows near the poles cover less solid angle, so weight each texel's
+        // luminance by sin(theta) at the row centre.
+        let weights: Vec<f64> = texels
+            .iter()
+            .enumerate()
+            .map(|(i, &texel)| {
+                let row = (i / width as usize) as f64;
+                luminance(texel) * (fPI * (row + 0.5) / height as f64).sin()
+            })
+            .collect();
+        let distribution = Distribution2D::from(&weights, width as usize, height as usize);
+
+        Arc::new(Self {
+            width,
+            height,
+            texels,
+            rotation: degrees_to_radians(rotation),
+            intensity,
+            distribution,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Image coordinates in [0, 1] for a world direction, with v = 0 at the
+    /// top row.
+    pub fn direction_to_uv(&self, direction: Vec3) -> (f64, f64) {
+        let d = Vec3::unit_vector(direction);
+        let theta = d.y.clamp(-1.0, 1.0).acos();
+        let phi = (-d.z).atan2(d.x) + fPI - self.rotation;
+        ((phi / (2.0 * fPI)).rem_euclid(1.0), theta / fPI)
+    }
+
+    /// Unit world direction for image coordinates, inverting `direction_to_uv`.
+    pub fn uv_to_direction(&self, u: f64, v: f64) -> Vec3 {
+        let theta = v * fPI;
+        let phi = u * 2.0 * fPI + self.rotation - fPI;
+        let sin_theta = theta.sin();
+        Vec3::from(sin_theta * phi.cos(), theta.cos(), -sin_theta * phi.sin())
+    }
+
+    fn texel(&self, x: i64, y: i64) -> Color {
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.clamp(0, self.height as i64 - 1) as usize;
+        self.texels[y * self.width as usize + x]
+    }
+
+    /// Bilinear lookup that wraps around horizontally and clamps at the poles.
+    fn lookup(&self, u: f64, v: f64) -> Color {
+        let x = u * self.width as f64 - 0.5;
+        let y = v * self.height as f64 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = (1.0 - fx) * self.texel(x0, y0) + fx * self.texel(x0 + 1, y0);
+        let bottom = (1.0 - fx) * self.texel(x0, y0 + 1) + fx * self.texel(x0 + 1, y0 + 1);
+        (1.0 - fy) * top + fy * bottom
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn value(&self, direction: Vec3) -> Color {
+        let (u, v) = self.direction_to_uv(direction);
+        self.intensity * self.lookup(u, v)
+    }
+
+    /// Converts the image-space density to solid angle; the equirectangular
+    /// map stretches each texel by 2 pi^2 sin(theta).
+    fn sample(&self) -> (Vec3, f64) {
+        let ((u, v), pdf) = self.distribution.sample(rand_f64(), rand_f64());
+        let sin_theta = (v * fPI).sin();
+        let direction = self.uv_to_direction(u, v);
+        if sin_theta <= 0.0 {
+            return (direction, 0.0);
+        }
+        (direction, pdf / (2.0 * fPI * fPI * sin_theta))
+    }
+
+    fn pdf(&self, direction: Vec3) -> f64 {
+        let (u, v) = self.direction_to_uv(direction);
+        let sin_theta = (v * fPI).sin();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+        self.distribution.pdf(u, v) / (2.0 * fPI * fPI * sin_theta)
+    }
+
+    fn importance_sampled(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+use crate::background::Background;
+use crate::color::Color;
+use crate::utils::Vec3;
+
+/// Blends linearly from `bottom` straight down to `top` straight up, where
+/// up is `up`. The default is the white-to-blue sky.
+#[derive(Copy, Clone)]
+pub struct GradientBackground {
+    bottom: Color,
+    top: Color,
+    up: Vec3,
+}
+
+impl GradientBackground {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(bottom: impl Into<Color>, top: impl Into<Color>, up: impl Into<Vec3>) -> Arc<Self> {
+        Arc::new(Self {
+            bottom: bottom.into(),
+            top: top.into(),
+            up: Vec3::unit_vector(up.into()),
+        })
+    }
+}
+
+impl Default for GradientBackground {
+    fn default() -> Self {
+        Self {
+            bottom: Color::ones(),
+            top: Color::from(0.5, 0.7, 1.0),
+            up: Vec3::unit_y(),
+        }
+    }
+}
+
+impl Background for GradientBackground {
+    fn value(&self, direction: Vec3) -> Color {
+        let a = 0.5 * (Vec3::dot(Vec3::unit_vector(direction), self.up) + 1.0);
+        (1.0 - a) * self.bottom + a * self.top
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+use crate::background::Background;
+use crate::color::Color;
+use crate::utils::Vec3;
+
+#[derive(Default, Copy, Clone)]
+pub struct SolidBackground {
+    color: Color,
+}
+
+impl SolidBackground {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(color: impl Into<Color>) -> Arc<Self> {
+        Arc::new(Self {
+            color: color.into(),
+        })
+    }
+
+    /// No light from outside, for scenes lit only by emitters.
+    pub fn black() -> Arc<Self> {
+        Self::from(Color::zeros())
+    }
+}
+
+impl Background for SolidBackground {
+    fn value(&self, _direction: Vec3) -> Color {
+        self.color
+    }
+}
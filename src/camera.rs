@@ -1,37 +1,78 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Instant;
+use crate::background::{Background, GradientBackground};
 use crate::color::{write_color, Color};
-use crate::hittable::Hittable;
-use crate::image::Image;
+use crate::hittable::{HitRecord, Hittable};
+use crate::image::{AccumulationBuffer, Image, PixelStats};
+use crate::pdf::{BackgroundPdf, HittablePdf, LightPdf, MisHeuristic, MixturePdf, Pdf};
 use crate::utils::{degrees_to_radians, Interval};
 use crate::ray::Ray;
 use crate::utils::rand_f64;
 use crate::utils::{Point3, Vec3};
 
-#[derive(Default, Copy, Clone)]
-struct Basis {
-    u: Vec3,
-    v: Vec3,
-    w: Vec3,
-}
+mod tile;
+mod progressive;
+mod adaptive;
+mod shutter;
+
+pub use tile::{Tile, TileOrder};
+pub use progressive::Progressive;
+pub use adaptive::Adaptive;
+pub use shutter::{Shutter, ShutterCurve};
 
-#[derive(Default, Copy, Clone)]
+#[derive(Clone)]
 pub struct Camera {
     samples_per_pixel: u32,
-    pixel_sample_scale: f64,
     max_depth: u32,
-    vertical_fov: f64,
     center: Point3,
     delta_x: Vec3,
     delta_y: Vec3,
-    upper_left: Vec3,
     pixel00: Vec3,
-    basis: Basis,
-    look_from: Point3,
-    look_at: Point3,
-    up: Vec3,
     defocus_angle: f64,
     focus_distance: f64,
     defocus_disk_x: Vec3,
     defocus_disk_y: Vec3,
+    threads: usize,
+    tile_size: u32,
+    tile_order: TileOrder,
+    adaptive: Option<Adaptive>,
+    image_height: u32,
+    shutter: Shutter,
+    background: Arc<dyn Background>,
+    lights: Option<Arc<dyn Hittable>>,
+    mis_heuristic: MisHeuristic,
+    light_weight: f64,
+    russian_roulette: Option<u32>,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            samples_per_pixel: 0,
+            max_depth: 0,
+            center: Point3::zeros(),
+            delta_x: Vec3::zeros(),
+            delta_y: Vec3::zeros(),
+            pixel00: Vec3::zeros(),
+            defocus_angle: 0.0,
+            focus_distance: 0.0,
+            defocus_disk_x: Vec3::zeros(),
+            defocus_disk_y: Vec3::zeros(),
+            threads: 0,
+            tile_size: 0,
+            tile_order: TileOrder::default(),
+            adaptive: None,
+            image_height: 0,
+            shutter: Shutter::default(),
+            background: Arc::new(GradientBackground::default()),
+            lights: None,
+            mis_heuristic: MisHeuristic::default(),
+            light_weight: 0.5,
+            russian_roulette: None,
+        }
+    }
 }
 
 impl Camera {
@@ -39,6 +80,7 @@ impl Camera {
         Self::default()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn from(
         image: &Image,
         samples_per_pixel: u32, 
@@ -60,7 +102,6 @@ impl Camera {
         let w = Vec3::unit_vector(look_from - look_at);
         let u = Vec3::unit_vector(Vec3::cross(up, w));
         let v = Vec3::cross(w, u);
-        let pixel_sample_scale = 1.0 / samples_per_pixel as f64;
         let center = look_from;
         let x = width * u;
         let y = height * -v;
@@ -73,54 +114,278 @@ impl Camera {
         let defocus_disk_y = v * defocus_radius;
         Self {
             samples_per_pixel,
-            pixel_sample_scale,
             max_depth,
-            vertical_fov,
             center,
             delta_x,
             delta_y,
-            upper_left,
             pixel00,
-            basis: Basis {
-                w,
-                u,
-                v,
-            },
-            look_from,
-            look_at,
-            up,
             defocus_angle,
             focus_distance,
             defocus_disk_x,
-            defocus_disk_y
+            defocus_disk_y,
+            threads: Self::available_threads(),
+            tile_size: 32,
+            tile_order: TileOrder::Spiral,
+            adaptive: None,
+            image_height: image.height,
+            shutter: Shutter::default(),
+            background: Arc::new(GradientBackground::default()),
+            lights: None,
+            mis_heuristic: MisHeuristic::default(),
+            light_weight: 0.5,
+            russian_roulette: None,
+        }
+    }
+
+    /// Sets the number of worker threads used by `render`; 0 uses every available core.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = if threads == 0 {Self::available_threads()} else {threads};
+    }
+
+    pub fn set_tile_size(&mut self, tile_size: u32) {
+        self.tile_size = tile_size.max(1);
+    }
+
+    pub fn set_tile_order(&mut self, tile_order: TileOrder) {
+        self.tile_order = tile_order;
+    }
+
+    /// Enables adaptive sampling, which replaces the fixed `samples_per_pixel`
+    /// with per-pixel bounds, or turns it off with `None`.
+    pub fn set_adaptive(&mut self, adaptive: Option<Adaptive>) {
+        self.adaptive = adaptive;
+    }
+
+    pub fn set_shutter(&mut self, shutter: Shutter) {
+        self.shutter = shutter;
+    }
+
+    /// Sets what rays that leave the scene see; the default is the sky gradient.
+    pub fn set_background(&mut self, background: Arc<dyn Background>) {
+        self.background = background;
+    }
+
+    /// Emitters to sample directly at every non-specular bounce, combined
+    /// with BSDF sampling by multiple importance sampling. `None` relies on
+    /// bounces alone, apart from an importance-sampled background. Panics if
+    /// `lights` does not support sampling.
+    pub fn set_lights(&mut self, lights: Option<Arc<dyn Hittable>>) {
+        if let Some(lights) = &lights {
+            assert!(lights.supports_sampling(), "lights must support sampling");
         }
+        self.lights = lights;
+    }
+
+    /// How light and BSDF samples are weighted against each other; the
+    /// default is the power heuristic.
+    pub fn set_mis_heuristic(&mut self, mis_heuristic: MisHeuristic) {
+        self.mis_heuristic = mis_heuristic;
+    }
+
+    /// Fraction of shadow rays aimed at `lights` rather than the background
+    /// when both can be sampled, clamped to [0, 1]. Defaults to 0.5.
+    pub fn set_light_weight(&mut self, light_weight: f64) {
+        self.light_weight = light_weight.clamp(0.0, 1.0);
+    }
+
+    /// Ends paths at random once they are `min_depth` bounces long, with
+    /// survivors reweighted so the image stays unbiased. While enabled,
+    /// `max_depth` only acts as a safety limit and should be set well above
+    /// `min_depth`. `None` ends every path at `max_depth`.
+    pub fn set_russian_roulette(&mut self, min_depth: Option<u32>) {
+        self.russian_roulette = min_depth;
+    }
+
+    fn available_threads() -> usize {
+        thread::available_parallelism().map_or(1, |n| n.get())
     }
 
     pub fn render(&self, image: &mut Image, world: &dyn Hittable) {
-        for (y, row) in image.buffer.enumerate_rows_mut() {
-            println!("\rScanlines remaining: {}          ", image.height - y);
-            for (x, _, pixel) in row {
-                let mut color = Color::zeros();
-                for sample in 0..self.samples_per_pixel {
-                    let ray = self.get_ray(x,y);
-                    color += Self::ray_color(ray, self.max_depth, world);
+        self.render_with(image, world, |_, _| {});
+    }
+
+    /// Renders like `render`, calling `on_tile` after each finished bucket has
+    /// been written so partial results can be displayed or saved.
+    pub fn render_with(&self, image: &mut Image, world: &dyn Hittable, mut on_tile: impl FnMut(&Image, &Tile)) {
+        let tile_count = tile::tiles(image.width, image.height, self.tile_size, self.tile_order).len();
+        let mut remaining = tile_count;
+        let mut accumulation = AccumulationBuffer::from(image.width, image.height);
+
+        self.render_tiles(
+            image.width,
+            image.height,
+            |tile| {
+                tile.pixels()
+                    .map(|(x, y)| match &self.adaptive {
+                        Some(adaptive) => self.sample_pixel_adaptive(x, y, PixelStats::new(), adaptive, world),
+                        None => self.sample_pixel(x, y, self.samples_per_pixel, world),
+                    })
+                    .collect::<Vec<PixelStats>>()
+            },
+            |tile, pixels| {
+                println!("\rTiles remaining: {}          ", remaining);
+                remaining -= 1;
+                for ((x, y), stats) in tile.pixels().zip(pixels) {
+                    write_color(image.buffer.get_pixel_mut(x, y), stats.mean());
+                    accumulation.add(x, y, stats);
                 }
-                write_color(pixel, self.pixel_sample_scale * color);
+                on_tile(image, &tile);
+            },
+        );
+
+        self.save_sample_counts(&accumulation);
+        println!("\rDone                             \n");
+    }
+
+    /// Accumulates `settings.samples_per_pass` samples per pixel per pass until
+    /// `samples_per_pixel` is reached or the time limit expires, saving a
+    /// preview of the running average every `settings.preview_every` passes.
+    /// With adaptive sampling, converged pixels are skipped in later passes.
+    pub fn render_progressive(&self, image: &mut Image, world: &dyn Hittable, settings: &Progressive) {
+        let start = Instant::now();
+        let samples_per_pass = settings.samples_per_pass.max(1);
+        let total_samples = self.adaptive.as_ref().map_or(self.samples_per_pixel, |adaptive| adaptive.max_samples);
+        let passes = total_samples.div_ceil(samples_per_pass);
+        let mut accumulation = AccumulationBuffer::from(image.width, image.height);
+
+        for pass in 1..=passes {
+            let samples = samples_per_pass.min(total_samples - (pass - 1) * samples_per_pass);
+            let converged: Vec<bool> = match &self.adaptive {
+                Some(adaptive) => (0..image.height)
+                    .flat_map(|y| (0..image.width).map(move |x| (x, y)))
+                    .map(|(x, y)| adaptive.converged(&accumulation.pixel(x, y)))
+                    .collect(),
+                None => Vec::new(),
+            };
+            if !converged.is_empty() && converged.iter().all(|&done| done) {
+                println!("\rAll pixels converged after {} passes", pass - 1);
+                break;
+            }
+
+            self.render_tiles(
+                image.width,
+                image.height,
+                |tile| {
+                    tile.pixels()
+                        .map(|(x, y)| {
+                            let index = y as usize * image.width as usize + x as usize;
+                            if converged.get(index).copied().unwrap_or(false) {
+                                PixelStats::new()
+                            } else {
+                                self.sample_pixel(x, y, samples, world)
+                            }
+                        })
+                        .collect::<Vec<PixelStats>>()
+                },
+                |tile, pixels| {
+                    for ((x, y), stats) in tile.pixels().zip(pixels) {
+                        accumulation.add(x, y, stats);
+                    }
+                },
+            );
+
+            println!("\rPass {}/{} ({} samples per pixel)          ", pass, passes, (pass * samples_per_pass).min(total_samples));
+            let out_of_time = settings.time_limit.is_some_and(|limit| start.elapsed() >= limit);
+
+            if let Some(path) = &settings.preview_path
+                && settings.preview_every > 0
+                && pass % settings.preview_every == 0
+                && pass < passes
+                && !out_of_time
+            {
+                accumulation.resolve(image);
+                image.save(path);
+            }
+
+            if out_of_time {
+                println!("\rTime limit reached after {} passes", pass);
+                break;
             }
         }
 
+        accumulation.resolve(image);
+        self.save_sample_counts(&accumulation);
         println!("\rDone                             \n");
     }
 
+    fn save_sample_counts(&self, accumulation: &AccumulationBuffer) {
+        if let Some(adaptive) = &self.adaptive
+            && let Some(path) = &adaptive.sample_count_path
+        {
+            accumulation.sample_count_image(adaptive.max_samples).save(path);
+        }
+    }
+
+    /// Fans the image's buckets out to the worker threads, running `render_tile`
+    /// on the workers and `on_result` on the calling thread as tiles complete.
+    fn render_tiles<T: Send>(
+        &self,
+        width: u32,
+        height: u32,
+        render_tile: impl Fn(Tile) -> T + Sync,
+        mut on_result: impl FnMut(Tile, T),
+    ) {
+        let tiles = tile::tiles(width, height, self.tile_size, self.tile_order);
+        let next_tile = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.max(1) {
+                let sender = sender.clone();
+                let next_tile = &next_tile;
+                let tiles = &tiles;
+                let render_tile = &render_tile;
+                scope.spawn(move || {
+                    while let Some(tile) = tiles.get(next_tile.fetch_add(1, Ordering::Relaxed)) {
+                        if sender.send((*tile, render_tile(*tile))).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(sender);
+
+            for (tile, result) in receiver {
+                on_result(tile, result);
+            }
+        });
+    }
+
+    /// Statistics of `samples` radiance samples through pixel `(x, y)`.
+    fn sample_pixel(&self, x: u32, y: u32, samples: u32, world: &dyn Hittable) -> PixelStats {
+        let mut stats = PixelStats::new();
+        for _ in 0..samples {
+            let ray = self.get_ray(x,y);
+            stats.add(self.ray_color(ray, world));
+        }
+        stats
+    }
+
+    /// Keeps adding batches of samples to `stats` until the pixel converges.
+    fn sample_pixel_adaptive(&self, x: u32, y: u32, mut stats: PixelStats, adaptive: &Adaptive, world: &dyn Hittable) -> PixelStats {
+        while !adaptive.converged(&stats) {
+            let remaining = adaptive.max_samples - stats.samples;
+            let batch = if stats.samples < adaptive.min_samples {
+                adaptive.min_samples - stats.samples
+            } else {
+                adaptive.batch_size.max(1).min(remaining)
+            };
+            stats.merge(self.sample_pixel(x, y, batch, world));
+        }
+        stats
+    }
+
     fn get_ray(&self, x: u32, y: u32) -> Ray {
         let offset = Self::sample_square();
         let sample = self.pixel00 + ((x as f64 + offset.x) * self.delta_x) + ((y as f64 + offset.y) * self.delta_y);
 
         let origin = if self.defocus_angle <= 0.0 {self.center} else {self.defocus_disk_sample()};
         let direction = sample - origin;
-        let time = rand_f64();
+        let time = self.shutter.sample((y as f64 + offset.y + 0.5) / self.image_height.max(1) as f64);
 
-        Ray::with_time(origin, direction, time)
+        // The pixel grid sits at the focus distance, so one pixel subtends
+        // roughly this angle.
+        Ray::with_time(origin, direction, time).with_spread(self.delta_x.length() / self.focus_distance)
     }
 
     fn sample_square() -> Vec3 {
@@ -132,20 +397,150 @@ impl Camera {
         self.center + (p.x * self.defocus_disk_x) + (p.y * self.defocus_disk_y)
     }
 
-    fn ray_color(ray: Ray, depth: u32, world: &dyn Hittable) -> Color {
-        if depth <= 0 {
+    /// Follows one path, adding the emission it finds scaled by the product
+    /// of the bounce weights so far.
+    fn ray_color(&self, ray: Ray, world: &dyn Hittable) -> Color {
+        let mut radiance = Color::zeros();
+        let mut throughput = Color::ones();
+        let mut ray = ray;
+        // BSDF density of the last bounce and the light distribution there,
+        // when light sampling ran at it.
+        let mut previous: Option<(f64, LightPdf)> = None;
+        let mut depth = 0;
+
+        loop {
+            // Past the last bounce the ray is still traced when light sampling
+            // ran there, since that sample was weighted against this ray
+            // picking up the same emission.
+            if depth >= self.max_depth && previous.is_none() {
+                break;
+            }
+
+            let Some(rec) = world.hit(ray, Interval::from(0.001, f64::INFINITY)) else {
+                let background = self.background.value(ray.direction);
+                radiance += throughput * self.emission_weight(ray.direction, &previous) * background;
+                break;
+            };
+
+            let emitted = rec.material.emitted(rec.u, rec.v, rec.p);
+            if !emitted.near_zero() {
+                radiance += throughput * self.emission_weight(ray.direction, &previous) * emitted;
+            }
+            if depth >= self.max_depth {
+                break;
+            }
+            let Some(scatter) = rec.material.scatter(ray, &rec) else {
+                break;
+            };
+
+            let light_pdf = if scatter.is_specular { None } else { self.light_pdf(rec.p, ray.time) };
+            previous = light_pdf.map(|light_pdf| {
+                radiance += throughput * self.sample_light(ray, &rec, world, &light_pdf);
+                (scatter.pdf, light_pdf)
+            });
+
+            throughput = throughput * scatter.weight();
+            ray = scatter.ray;
+            depth += 1;
+
+            if self.russian_roulette.is_some_and(|min_depth| depth >= min_depth) {
+                // Capping survival below one keeps even lossless paths finite.
+                let survival = throughput.x.max(throughput.y).max(throughput.z).min(0.95);
+                if survival <= 0.0 || rand_f64() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+        }
+
+        radiance
+    }
+
+    /// Distribution used for direct lighting at `origin`: the lights, an
+    /// importance-sampled background, or both mixed by `light_weight`.
+    fn light_pdf(&self, origin: Point3, time: f64) -> Option<LightPdf<'_>> {
+        let lights = self.lights.as_ref().map(|lights| HittablePdf::from(lights.as_ref(), origin, time));
+        let background = self
+            .background
+            .importance_sampled()
+            .then(|| BackgroundPdf::from(self.background.as_ref()));
+
+        match (lights, background) {
+            (Some(lights), Some(background)) => Some(LightPdf::Both(MixturePdf::from(lights, background, self.light_weight))),
+            (Some(lights), None) => Some(LightPdf::Lights(lights)),
+            (None, Some(background)) => Some(LightPdf::Background(background)),
+            (None, None) => None,
+        }
+    }
+
+    /// MIS weight for emission reached along `direction` by BSDF sampling
+    /// at the previous bounce.
+    fn emission_weight(&self, direction: Vec3, previous: &Option<(f64, LightPdf)>) -> f64 {
+        match previous {
+            Some((bsdf_pdf, light_pdf)) => self.mis_heuristic.weight(*bsdf_pdf, light_pdf.value(direction)),
+            None => 1.0,
+        }
+    }
+
+    /// Light reaching `rec` along one shadow ray drawn from `light_pdf`,
+    /// weighted against the BSDF having sampled the same direction.
+    fn sample_light(&self, ray: Ray, rec: &HitRecord, world: &dyn Hittable, light_pdf: &LightPdf) -> Color {
+        let direction = light_pdf.generate();
+        let pdf = light_pdf.value(direction);
+        if pdf <= 0.0 {
             return Color::zeros();
         }
 
-        if let Some(rec) = world.hit(ray, Interval::from(0.001, f64::INFINITY)) {
-            if let Some((scattered, attenuation)) = rec.clone().material.scatter(ray, rec) {
-                return attenuation * Self::ray_color(scattered, depth-1, world);
-            }
+        let bsdf = rec.material.eval(ray, rec, direction);
+        if bsdf.near_zero() {
             return Color::zeros();
         }
 
-        let unit_direction = Vec3::unit_vector(ray.direction);
-        let a = 0.5 * (unit_direction.y + 1.0);
-        (1.0 - a) * Color::ones() + a * Color::from(0.5, 0.7, 1.0)
+        let shadow_ray = Ray::with_time(rec.p, direction, ray.time);
+        let radiance = match world.hit(shadow_ray, Interval::from(0.001, f64::INFINITY)) {
+            Some(light) => light.material.emitted(light.u, light.v, light.p),
+            None => self.background.value(direction),
+        };
+        let weight = self.mis_heuristic.weight(pdf, rec.material.pdf(ray, rec, direction));
+        weight * bsdf * radiance / pdf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::background::SolidBackground;
+    use crate::hittable::{BvhNode, HittableList, Quad, Sphere};
+    use crate::material::{DiffuseLight, Lambertian};
+
+    #[test]
+    #[should_panic(expected = "lights must support sampling")]
+    fn set_lights_rejects_unsampleable_objects() {
+        let sphere = Sphere::from((0.0, 0.0, 0.0), 1.0, Lambertian::from((1.0, 1.0, 1.0)));
+        let mut camera = Camera::new();
+        camera.set_lights(Some(BvhNode::from(HittableList::from(vec![sphere]))));
+    }
+
+    #[test]
+    fn last_bounce_keeps_both_halves_of_its_light_estimate() {
+        // Light can only reach the eye by one bounce off the floor, so every
+        // depth limit from one up must see the same direct lighting.
+        let light = Quad::from((-1.0, 1.0, -1.0), (2.0, 0.0, 0.0), (0.0, 0.0, 2.0), DiffuseLight::from((4.0, 4.0, 4.0)));
+        let mut world = HittableList::new();
+        world.add(Quad::from((-50.0, 0.0, -50.0), (100.0, 0.0, 0.0), (0.0, 0.0, 100.0), Lambertian::from((0.5, 0.5, 0.5))));
+        world.add(light.clone());
+
+        let image = Image::with_size(1, 1);
+        let brightness = |max_depth: u32| {
+            let mut camera = Camera::from(&image, 1, max_depth, 90.0, (0.0, 0.5, 0.0), (0.0, 0.0, 0.0), (0.0, 0.0, 1.0), 0.0, 1.0);
+            camera.set_background(SolidBackground::from((0.0, 0.0, 0.0)));
+            camera.set_lights(Some(light.clone()));
+            let ray = Ray::from((0.0, 0.5, 0.0), (0.0, -1.0, 0.0));
+            let samples = 40_000;
+            (0..samples).map(|_| camera.ray_color(ray, &world).x).sum::<f64>() / samples as f64
+        };
+
+        let (one, three) = (brightness(1), brightness(3));
+        assert!((one - three).abs() < 0.02 * three, "{one} != {three}");
     }
 }
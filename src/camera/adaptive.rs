@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+use crate::image::PixelStats;
+
+/// Settings for adaptive sampling: each pixel takes at least `min_samples`,
+/// then keeps sampling in batches of `batch_size` until its relative error
+/// falls below `threshold` or it reaches `max_samples`.
+#[derive(Clone)]
+pub struct Adaptive {
+    pub min_samples: u32,
+    pub max_samples: u32,
+    pub batch_size: u32,
+    pub threshold: f64,
+    pub sample_count_path: Option<PathBuf>,
+}
+
+impl Adaptive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(min_samples: u32, max_samples: u32, threshold: f64) -> Self {
+        let min_samples = min_samples.max(2);
+        Self {
+            min_samples,
+            max_samples: max_samples.max(min_samples),
+            threshold,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_sample_count_path(self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            sample_count_path: Some(path.into()),
+            ..self
+        }
+    }
+
+    pub fn converged(&self, stats: &PixelStats) -> bool {
+        stats.samples >= self.max_samples
+            || (stats.samples >= self.min_samples && stats.relative_error() < self.threshold)
+    }
+}
+
+impl Default for Adaptive {
+    fn default() -> Self {
+        Self {
+            min_samples: 16,
+            max_samples: 1024,
+            batch_size: 8,
+            threshold: 0.01,
+            sample_count_path: None,
+        }
+    }
+}
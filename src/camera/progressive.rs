@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Settings for `Camera::render_progressive`: the image is refined in passes
+/// of `samples_per_pass` until the camera's `samples_per_pixel` is reached or
+/// `time_limit` runs out, saving a preview every `preview_every` passes.
+#[derive(Clone)]
+pub struct Progressive {
+    pub samples_per_pass: u32,
+    pub preview_every: u32,
+    pub preview_path: Option<PathBuf>,
+    pub time_limit: Option<Duration>,
+}
+
+impl Progressive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(samples_per_pass: u32, preview_every: u32, preview_path: Option<impl Into<PathBuf>>) -> Self {
+        Self {
+            samples_per_pass: samples_per_pass.max(1),
+            preview_every,
+            preview_path: preview_path.map(Into::into),
+            time_limit: None,
+        }
+    }
+
+    pub fn with_time_limit(self, time_limit: Duration) -> Self {
+        Self {
+            time_limit: Some(time_limit),
+            ..self
+        }
+    }
+}
+
+impl Default for Progressive {
+    fn default() -> Self {
+        Self {
+            samples_per_pass: 4,
+            preview_every: 1,
+            preview_path: None,
+            time_limit: None,
+        }
+    }
+}
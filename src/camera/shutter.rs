@@ -0,0 +1,85 @@
+use crate::utils::rand_f64;
+
+/// How much light the shutter lets through over its open interval.
+#[derive(Default, Copy, Clone, PartialEq, Debug)]
+pub enum ShutterCurve {
+    /// Fully open for the whole interval.
+    #[default]
+    Box,
+    /// Opens linearly to a peak at mid-exposure, then closes linearly.
+    Triangle,
+    /// Ramps open over the given fraction of the interval (at most one half),
+    /// stays open, then ramps closed over the same fraction.
+    Trapezoid(f64),
+}
+
+/// Exposure timing for `Camera`: rays are given times between `open` and
+/// `close`, weighted by `curve`. With a non-zero `rolling` readout, each
+/// scanline's exposure starts `rolling` later from the top row to the bottom.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Shutter {
+    pub open: f64,
+    pub close: f64,
+    pub curve: ShutterCurve,
+    pub rolling: f64,
+}
+
+impl Shutter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(open: f64, close: f64, curve: ShutterCurve) -> Self {
+        Self {
+            open,
+            close: close.max(open),
+            curve,
+            rolling: 0.0,
+        }
+    }
+
+    pub fn with_rolling(self, readout: f64) -> Self {
+        Self {
+            rolling: readout.max(0.0),
+            ..self
+        }
+    }
+
+    /// Samples a ray time for a pixel `row_fraction` of the way down the image.
+    pub fn sample(&self, row_fraction: f64) -> f64 {
+        let ramp = match self.curve {
+            ShutterCurve::Box => 0.0,
+            ShutterCurve::Triangle => 0.5,
+            ShutterCurve::Trapezoid(ramp) => ramp.clamp(0.0, 0.5),
+        };
+        let x = sample_trapezoid(rand_f64(), ramp);
+        self.open + self.rolling * row_fraction + x * (self.close - self.open)
+    }
+}
+
+impl Default for Shutter {
+    fn default() -> Self {
+        Self {
+            open: 0.0,
+            close: 1.0,
+            curve: ShutterCurve::Box,
+            rolling: 0.0,
+        }
+    }
+}
+
+/// Inverts the CDF of a trapezoid on [0, 1] whose sides ramp over `ramp`.
+fn sample_trapezoid(u: f64, ramp: f64) -> f64 {
+    if ramp <= 0.0 {
+        return u;
+    }
+    let height = 1.0 / (1.0 - ramp);
+    let ramp_area = 0.5 * ramp * height;
+    if u < ramp_area {
+        (2.0 * u * ramp / height).sqrt()
+    } else if u > 1.0 - ramp_area {
+        1.0 - (2.0 * (1.0 - u) * ramp / height).sqrt()
+    } else {
+        ramp + (u - ramp_area) / height
+    }
+}
@@ -0,0 +1,130 @@
+#[derive(Default, Copy, Clone)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Tile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn pixel_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    /// Pixel coordinates covered by the tile, in row-major order.
+    pub fn pixels(&self) -> impl Iterator<Item = (u32, u32)> + use<> {
+        let Self { x, y, width, height } = *self;
+        (y..y + height).flat_map(move |py| (x..x + width).map(move |px| (px, py)))
+    }
+}
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TileOrder {
+    #[default]
+    Scanline,
+    Spiral,
+    Hilbert,
+}
+
+/// Splits a `width` x `height` image into square buckets of `size` pixels,
+/// clipped at the right and bottom edges, and returns them in render order.
+pub fn tiles(width: u32, height: u32, size: u32, order: TileOrder) -> Vec<Tile> {
+    let size = size.max(1);
+    let columns = width.div_ceil(size);
+    let rows = height.div_ceil(size);
+
+    let tile_at = |column: u32, row: u32| {
+        let x = column * size;
+        let y = row * size;
+        Tile::from(x, y, size.min(width - x), size.min(height - y))
+    };
+
+    match order {
+        TileOrder::Scanline => (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| tile_at(column, row))
+            .collect(),
+        TileOrder::Spiral => spiral(columns, rows)
+            .into_iter()
+            .map(|(column, row)| tile_at(column, row))
+            .collect(),
+        TileOrder::Hilbert => {
+            let n = columns.max(rows).next_power_of_two();
+            let mut cells: Vec<(u32, u32)> = (0..rows)
+                .flat_map(|row| (0..columns).map(move |column| (column, row)))
+                .collect();
+            cells.sort_by_key(|&(column, row)| hilbert_index(n, column, row));
+            cells.into_iter().map(|(column, row)| tile_at(column, row)).collect()
+        }
+    }
+}
+
+/// Walks a square spiral outwards from the centre cell, keeping only cells
+/// inside the grid.
+fn spiral(columns: u32, rows: u32) -> Vec<(u32, u32)> {
+    let total = (columns * rows) as usize;
+    let mut cells = Vec::with_capacity(total);
+    if total == 0 {
+        return cells;
+    }
+
+    let (mut x, mut y) = (((columns - 1) / 2) as i64, ((rows - 1) / 2) as i64);
+    let (mut dx, mut dy) = (1i64, 0i64);
+    let mut leg_length = 1;
+
+    let visit = |x: i64, y: i64, cells: &mut Vec<(u32, u32)>| {
+        if x >= 0 && y >= 0 && x < columns as i64 && y < rows as i64 {
+            cells.push((x as u32, y as u32));
+        }
+    };
+
+    visit(x, y, &mut cells);
+    while cells.len() < total {
+        for _ in 0..2 {
+            for _ in 0..leg_length {
+                x += dx;
+                y += dy;
+                visit(x, y, &mut cells);
+            }
+            (dx, dy) = (-dy, dx);
+        }
+        leg_length += 1;
+    }
+
+    cells
+}
+
+/// Distance of `(x, y)` along the Hilbert curve filling an `n` x `n` grid,
+/// where `n` is a power of two.
+fn hilbert_index(n: u32, x: u32, y: u32) -> u64 {
+    let (mut x, mut y) = (x, y);
+    let mut d = 0u64;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = u32::from(x & s > 0);
+        let ry = u32::from(y & s > 0);
+        d += s as u64 * s as u64 * ((3 * rx) ^ ry) as u64;
+        if ry == 0 {
+            if rx == 1 {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
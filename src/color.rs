@@ -23,6 +23,22 @@ fn linear_to_gamma(linear_component: f64) -> f64 {
     }
 }
 
+#[inline(always)]
+pub fn luminance(color: Color) -> f64 {
+    0.2126 * color.x + 0.7152 * color.y + 0.0722 * color.z
+}
+
+/// Inverse of `linear_to_gamma`, for decoding display-referred inputs such as
+/// 8-bit vertex colors.
+#[inline(always)]
+pub fn gamma_to_linear(gamma_component: f64) -> f64 {
+    if gamma_component > 0.0 {
+        gamma_component * gamma_component
+    } else {
+        0.0
+    }
+}
+
 pub fn write_color(pixel: &mut Rgb<u8>, color: Color) {
 
     let r = linear_to_gamma(color.x);
@@ -1,32 +1,55 @@
-use std::rc::Rc;
-use crate::utils::Interval;
+use std::sync::Arc;
+use crate::utils::{Aabb, Interval};
+use crate::color::Color;
 use crate::material::Material;
 use crate::ray::Ray;
 use crate::utils::{Point3, Vec3};
 
 mod sphere;
 mod hittable_list;
+mod bvh;
+mod triangle;
+mod triangle_mesh;
+mod quad;
+mod disk;
+mod cuboid;
+mod transformed;
+mod animated;
 
 #[derive(Clone)]
 pub struct HitRecord {
     pub p: Point3,
     pub normal: Vec3,
-    pub material: Rc<dyn Material>,
+    pub material: Arc<dyn Material>,
     pub t: f64,
+    pub u: f64,
+    pub v: f64,
     pub front_face: bool,
+    pub vertex_color: Option<Color>,
+    /// Approximate width of the ray's footprint in UV units, for texture
+    /// filtering. Starts out in world units; primitives convert it with
+    /// `with_uv_density`.
+    pub footprint: f64,
 }
 
 impl HitRecord {
-    pub fn from(p: impl Into<Point3>, t: f64, ray: impl Into<Ray>, material: Rc<dyn Material>, outward_normal: impl Into<Vec3>) -> Self {
+    pub fn from(p: impl Into<Point3>, t: f64, (u, v): (f64, f64), ray: impl Into<Ray>, material: Arc<dyn Material>, outward_normal: impl Into<Vec3>) -> Self {
         let p = p.into();
         let outward_normal = outward_normal.into();
         let ray = ray.into();
         let front_face = Vec3::dot(ray.direction, outward_normal) < 0.0;
+        // Oblique hits stretch the footprint; the cap stops it blowing up at
+        // grazing angles.
+        let cosine = Vec3::dot(Vec3::unit_vector(ray.direction), outward_normal).abs();
         Self {
             p,
             t,
+            u,
+            v,
             material,
             front_face,
+            vertex_color: None,
+            footprint: ray.width_at(t) / cosine.max(0.1),
             normal: if front_face {
                 outward_normal
             } else {
@@ -34,11 +57,182 @@ impl HitRecord {
             },
         }
     }
+
+    /// Replaces the geometric normal with an interpolated shading normal,
+    /// flipped to the side of the surface the ray hit.
+    pub fn with_shading_normal(self, shading_normal: impl Into<Vec3>) -> Self {
+        let shading_normal = shading_normal.into();
+        Self {
+            normal: if Vec3::dot(shading_normal, self.normal) < 0.0 {
+                -shading_normal
+            } else {
+                shading_normal
+            },
+            ..self
+        }
+    }
+
+    /// Scales the footprint by the UV units per world unit of the surface
+    /// around the hit.
+    pub fn with_uv_density(self, density: f64) -> Self {
+        Self {
+            footprint: self.footprint * density,
+            ..self
+        }
+    }
+
+    /// Attaches a color interpolated from per-vertex data, which diffuse
+    /// materials multiply into their albedo.
+    pub fn with_vertex_color(self, color: impl Into<Color>) -> Self {
+        Self {
+            vertex_color: Some(color.into()),
+            ..self
+        }
+    }
 }
 
-pub trait Hittable {
+pub trait Hittable: Send + Sync {
     fn hit(&self, ray: Ray, t: Interval) -> Option<HitRecord>;
+
+    fn bounding_box(&self) -> Aabb;
+
+    /// Density over solid angle, seen from `origin` at `time`, with which
+    /// `random` picks `direction`. Only meaningful when `supports_sampling`.
+    fn pdf_value(&self, _origin: Point3, _direction: Vec3, _time: f64) -> f64 {
+        0.0
+    }
+
+    /// A direction from `origin` towards a random point on the object.
+    fn random(&self, _origin: Point3, _time: f64) -> Vec3 {
+        Vec3::unit_x()
+    }
+
+    /// Whether `pdf_value` and `random` are implemented, so the object can be
+    /// sampled as a light.
+    fn supports_sampling(&self) -> bool {
+        false
+    }
+}
+
+/// Converts a uniform density over a flat surface of `area` to one over
+/// solid angle, for a hit at `t` along `direction`.
+pub(crate) fn area_pdf(direction: Vec3, t: f64, normal: Vec3, area: f64) -> f64 {
+    let distance_squared = t * t * direction.length_squared();
+    let cosine = (Vec3::dot(direction, normal) / direction.length()).abs();
+    if cosine <= 0.0 || area <= 0.0 {
+        return 0.0;
+    }
+    distance_squared / (cosine * area)
 }
 
 pub use sphere::Sphere;
-pub use hittable_list::HittableList;
\ No newline at end of file
+pub use hittable_list::HittableList;
+pub use bvh::{BvhNode, SahConfig};
+pub use triangle::Triangle;
+pub use triangle_mesh::TriangleMesh;
+pub use quad::Quad;
+pub use disk::Disk;
+pub use cuboid::Cuboid;
+pub use transformed::Transformed;
+pub use animated::{Animated, Keyframe};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::utils::{fPI, Mat4, Quat};
+
+    const ORIGIN: Point3 = Point3 { x: 0.2, y: -0.1, z: 2.0 };
+
+    fn material() -> Arc<dyn Material> {
+        Lambertian::from((0.5, 0.5, 0.5))
+    }
+
+    /// Monte Carlo estimate of the integral of `pdf_value` over all directions
+    /// seen from `ORIGIN`, from uniform sphere samples.
+    fn pdf_integral(object: &dyn Hittable) -> f64 {
+        let samples = 200_000;
+        let sum: f64 = (0..samples).map(|_| object.pdf_value(ORIGIN, Vec3::random_unit_vector(), 0.5)).sum();
+        4.0 * fPI * sum / samples as f64
+    }
+
+    fn samples_have_density(object: &dyn Hittable) -> bool {
+        (0..1000).all(|_| object.pdf_value(ORIGIN, object.random(ORIGIN, 0.5), 0.5) > 0.0)
+    }
+
+    #[test]
+    fn primitive_pdfs_integrate_to_one() {
+        let primitives: [(&str, Arc<dyn Hittable>); 4] = [
+            ("sphere", Sphere::from((0.0, 0.0, 0.0), 1.0, material())),
+            ("quad", Quad::from((-1.0, -1.0, 0.0), (2.0, 0.0, 0.0), (0.0, 2.0, 0.5), material())),
+            ("disk", Disk::from((0.0, 0.0, 0.0), (0.0, 0.3, 1.0), 1.2, material())),
+            ("triangle", Triangle::from((-1.5, -1.0, 0.0), (1.5, -1.0, 0.0), (0.0, 1.5, -0.5), material())),
+        ];
+        for (name, object) in primitives {
+            assert!(object.supports_sampling(), "{name}");
+            let integral = pdf_integral(object.as_ref());
+            assert!((integral - 1.0).abs() < 0.05, "{name}: pdf integrates to {integral}");
+            assert!(samples_have_density(object.as_ref()), "{name}");
+        }
+    }
+
+    #[test]
+    fn mesh_and_list_pdfs_integrate_to_one() {
+        let positions = vec![
+            Point3::from(-1.0, -1.0, 0.0),
+            Point3::from(1.0, -1.0, 0.0),
+            Point3::from(1.0, 1.0, 0.0),
+            Point3::from(-1.0, 1.0, 0.6),
+        ];
+        let open = TriangleMesh::from(positions, vec![[0, 1, 2], [0, 2, 3]], material());
+
+        // Every ray that enters a closed mesh leaves it through another face.
+        let corners = vec![
+            Point3::from(1.0, 1.0, 1.0),
+            Point3::from(1.0, -1.0, -1.0),
+            Point3::from(-1.0, 1.0, -1.0),
+            Point3::from(-1.0, -1.0, 1.0),
+        ];
+        let tetrahedron = TriangleMesh::from(corners, vec![[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]], material());
+
+        for mesh in [open, tetrahedron] {
+            let integral = pdf_integral(mesh.as_ref());
+            assert!((integral - 1.0).abs() < 0.05, "pdf integrates to {integral}");
+            assert!(samples_have_density(mesh.as_ref()));
+        }
+
+        let mut list = HittableList::new();
+        list.add(Sphere::from((-1.0, 0.0, 0.0), 0.6, material()));
+        list.add(Quad::from((0.5, -1.0, 0.0), (1.0, 0.0, 0.0), (0.0, 2.0, 0.0), material()));
+        assert!((pdf_integral(&list) - 1.0).abs() < 0.05);
+        assert!(samples_have_density(&list));
+    }
+
+    #[test]
+    fn instance_pdfs_integrate_to_one() {
+        let transform = Mat4::translation((0.3, 0.0, -0.5)) * Mat4::rotation_y(30.0) * Mat4::scaling((1.5, 0.6, 1.0));
+        let sphere = Sphere::from((0.0, 0.0, 0.0), 1.0, material());
+        let keyframes = vec![
+            Keyframe::from(0.0, Vec3::zeros(), Quat::identity(), (1.0, 1.5, 1.0)),
+            Keyframe::from(1.0, (1.0, 0.0, 0.0), Quat::from_axis_angle((0.0, 0.0, 1.0), 90.0), (1.0, 0.5, 1.0)),
+        ];
+        let instances: [Arc<dyn Hittable>; 2] = [Transformed::from(sphere.clone(), transform), Animated::from(sphere, keyframes)];
+        for instance in instances {
+            let integral = pdf_integral(instance.as_ref());
+            assert!((integral - 1.0).abs() < 0.05, "pdf integrates to {integral}");
+            assert!(samples_have_density(instance.as_ref()));
+        }
+    }
+
+    #[test]
+    fn only_sampled_objects_support_sampling() {
+        let bvh = BvhNode::from(HittableList::from(vec![Sphere::from((0.0, 0.0, 0.0), 1.0, material())]));
+        assert!(!bvh.supports_sampling());
+        assert!(!HittableList::new().supports_sampling());
+
+        let mut mixed = HittableList::new();
+        mixed.add(Sphere::from((0.0, 0.0, 0.0), 1.0, material()));
+        mixed.add(bvh);
+        assert!(!mixed.supports_sampling());
+    }
+}
@@ -0,0 +1,212 @@
+use std::sync::Arc;
+use crate::hittable::transformed::instance_pdf;
+use crate::hittable::{HitRecord, Hittable};
+use crate::ray::Ray;
+use crate::utils::{Aabb, Interval, Mat4, Point3, Quat, Vec3};
+
+/// Samples per keyframe segment used to bound the swept volume.
+const BOUND_SAMPLES: usize = 32;
+
+/// Placement of an animated object at `time`, applied as scale, then
+/// rotation, then translation.
+#[derive(Copy, Clone)]
+pub struct Keyframe {
+    pub time: f64,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Keyframe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(time: f64, translation: impl Into<Vec3>, rotation: Quat, scale: impl Into<Vec3>) -> Self {
+        Self {
+            time,
+            translation: translation.into(),
+            rotation,
+            scale: scale.into(),
+        }
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::translation(self.translation) * self.rotation.to_mat4() * Mat4::scaling(self.scale)
+    }
+
+    pub fn inverse_matrix(&self) -> Mat4 {
+        let inverse_scale = Vec3::from(1.0 / self.scale.x, 1.0 / self.scale.y, 1.0 / self.scale.z);
+        let conjugate = Quat::from(self.rotation.w, -self.rotation.x, -self.rotation.y, -self.rotation.z);
+        Mat4::scaling(inverse_scale) * conjugate.to_mat4() * Mat4::translation(-self.translation)
+    }
+}
+
+impl Default for Keyframe {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            translation: Vec3::zeros(),
+            rotation: Quat::identity(),
+            scale: Vec3::ones(),
+        }
+    }
+}
+
+/// `object` moved by keyframes interpolated at each ray's time: translation
+/// and scale linearly (or along a Catmull-Rom spline), rotation by slerp.
+/// Times outside the keyframes hold the first or last pose.
+#[derive(Clone)]
+pub struct Animated {
+    pub object: Arc<dyn Hittable>,
+    keyframes: Vec<Keyframe>,
+    spline: bool,
+    bbox: Aabb,
+}
+
+impl Animated {
+    pub fn from(object: Arc<dyn Hittable>, keyframes: Vec<Keyframe>) -> Arc<Self> {
+        Arc::new(Self::build(object, keyframes, false))
+    }
+
+    /// Like `from`, but the translation follows a Catmull-Rom spline through
+    /// the keyframes so paths curve smoothly instead of turning at each key.
+    pub fn with_spline(object: Arc<dyn Hittable>, keyframes: Vec<Keyframe>) -> Arc<Self> {
+        Arc::new(Self::build(object, keyframes, true))
+    }
+
+    fn build(object: Arc<dyn Hittable>, mut keyframes: Vec<Keyframe>, spline: bool) -> Self {
+        assert!(!keyframes.is_empty(), "animation needs at least one keyframe");
+        assert!(
+            keyframes.iter().all(|k| k.scale.x != 0.0 && k.scale.y != 0.0 && k.scale.z != 0.0),
+            "keyframe scale must be non-zero"
+        );
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        for keyframe in &mut keyframes {
+            keyframe.rotation = Quat::normalize(keyframe.rotation);
+        }
+
+        let mut animated = Self {
+            object,
+            keyframes,
+            spline,
+            bbox: Aabb::empty(),
+        };
+        animated.bbox = animated.swept_bounds();
+        animated
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Interpolated pose at `time`.
+    pub fn keyframe_at(&self, time: f64) -> Keyframe {
+        let keys = &self.keyframes;
+        let last = keys.len() - 1;
+        if time <= keys[0].time {
+            return keys[0];
+        }
+        if time >= keys[last].time {
+            return keys[last];
+        }
+
+        let i = keys.partition_point(|k| k.time <= time) - 1;
+        let (a, b) = (keys[i], keys[i + 1]);
+        let span = b.time - a.time;
+        let s = if span > 0.0 { (time - a.time) / span } else { 0.0 };
+
+        let translation = if self.spline {
+            let before = keys[i.saturating_sub(1)].translation;
+            let after = keys[(i + 2).min(last)].translation;
+            catmull_rom(before, a.translation, b.translation, after, s)
+        } else {
+            (1.0 - s) * a.translation + s * b.translation
+        };
+
+        Keyframe {
+            time,
+            translation,
+            rotation: Quat::slerp(a.rotation, b.rotation, s),
+            scale: (1.0 - s) * a.scale + s * b.scale,
+        }
+    }
+
+    /// Bounds the object's bounding sphere at closely spaced times, padded by
+    /// the distance moved between samples so nothing escapes between them.
+    fn swept_bounds(&self) -> Aabb {
+        let object_box = self.object.bounding_box();
+        let center = object_box.centroid();
+        let corner = Vec3::from(object_box.x.max, object_box.y.max, object_box.z.max);
+        let radius = (corner - center).length();
+
+        let first = self.keyframes[0].time;
+        let last = self.keyframes[self.keyframes.len() - 1].time;
+        let steps = BOUND_SAMPLES * self.keyframes.len();
+
+        let mut bbox = Aabb::empty();
+        let mut previous: Option<Vec3> = None;
+        for step in 0..=steps {
+            let time = first + (last - first) * step as f64 / steps as f64;
+            let keyframe = self.keyframe_at(time);
+            let world_center = keyframe.matrix().transform_point(center);
+            let scale = keyframe.scale.x.abs().max(keyframe.scale.y.abs()).max(keyframe.scale.z.abs());
+            let chord = previous.map_or(0.0, |p| (world_center - p).length());
+            let extent = radius * scale + chord;
+            let half = Vec3::from(extent, extent, extent);
+            bbox = Aabb::from_boxes(bbox, Aabb::from_points(world_center - half, world_center + half));
+            previous = Some(world_center);
+        }
+        bbox
+    }
+}
+
+impl Hittable for Animated {
+    fn hit(&self, ray: Ray, t: Interval) -> Option<HitRecord> {
+        let keyframe = self.keyframe_at(ray.time);
+        let transform = keyframe.matrix();
+        let inverse = keyframe.inverse_matrix();
+
+        let object_ray = Ray::with_time(
+            inverse.transform_point(ray.origin),
+            inverse.transform_vector(ray.direction),
+            ray.time,
+        )
+        .with_spread(ray.spread);
+
+        let rec = self.object.hit(object_ray, t)?;
+        Some(HitRecord {
+            p: transform.transform_point(rec.p),
+            normal: Vec3::unit_vector(inverse.transform_normal(rec.normal)),
+            ..rec
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        let inverse = self.keyframe_at(time).inverse_matrix();
+        instance_pdf(self.object.as_ref(), &inverse, origin, direction, time)
+    }
+
+    fn random(&self, origin: Point3, time: f64) -> Vec3 {
+        let keyframe = self.keyframe_at(time);
+        let local = self.object.random(keyframe.inverse_matrix().transform_point(origin), time);
+        keyframe.matrix().transform_vector(local)
+    }
+
+    fn supports_sampling(&self) -> bool {
+        self.object.supports_sampling()
+    }
+}
+
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, s: f64) -> Vec3 {
+    let s2 = s * s;
+    let s3 = s2 * s;
+    0.5 * ((2.0 * p1)
+        + (p2 - p0) * s
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * s2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * s3)
+}
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+use crate::hittable::{HitRecord, Hittable, HittableList};
+use crate::ray::Ray;
+use crate::utils::{Aabb, Interval};
+
+mod sah;
+
+pub use sah::{BvhStats, SahConfig};
+pub(crate) use sah::partition;
+
+#[derive(Clone)]
+pub struct BvhNode {
+    left: Arc<dyn Hittable>,
+    right: Arc<dyn Hittable>,
+    bbox: Aabb,
+}
+
+impl BvhNode {
+    pub fn from(list: HittableList) -> Arc<Self> {
+        let mut objects = list.objects().to_vec();
+        if objects.is_empty() {
+            let empty: Arc<dyn Hittable> = Arc::new(HittableList::new());
+            return Arc::new(Self {
+                left: empty.clone(),
+                right: empty,
+                bbox: Aabb::empty(),
+            });
+        }
+        Self::from_objects(&mut objects)
+    }
+
+    pub fn with_sah(list: HittableList, config: SahConfig) -> (Arc<Self>, BvhStats) {
+        let mut objects = list.objects().to_vec();
+        let mut stats = BvhStats::new();
+
+        let (root, cost) = match Self::build_interior(&mut objects, &config, &mut stats, 0) {
+            Some((root, cost)) => (Arc::new(root), cost),
+            None => {
+                // Too few objects to split, so the root holds a single leaf.
+                let (leaf, cost) = Self::build_leaf(&objects, &config, &mut stats, 0);
+                let empty: Arc<dyn Hittable> = Arc::new(HittableList::new());
+                (Arc::new(Self { left: leaf, right: empty, bbox: list.bounding_box() }), cost)
+            }
+        };
+        stats.sah_cost = cost;
+
+        (root, stats)
+    }
+
+    fn build_sah(
+        objects: &mut [Arc<dyn Hittable>],
+        config: &SahConfig,
+        stats: &mut BvhStats,
+        depth: usize,
+    ) -> (Arc<dyn Hittable>, f64) {
+        match Self::build_interior(objects, config, stats, depth) {
+            Some((node, cost)) => (Arc::new(node), cost),
+            None => Self::build_leaf(objects, config, stats, depth),
+        }
+    }
+
+    /// Splits `objects` where the SAH says it pays off, or returns `None` when
+    /// they are cheaper to keep in a leaf.
+    fn build_interior(
+        objects: &mut [Arc<dyn Hittable>],
+        config: &SahConfig,
+        stats: &mut BvhStats,
+        depth: usize,
+    ) -> Option<(Self, f64)> {
+        let mid = partition(objects, |object| object.bounding_box(), config)?;
+
+        stats.record_node(depth);
+        let bbox = objects
+            .iter()
+            .fold(Aabb::empty(), |bbox, object| Aabb::from_boxes(bbox, object.bounding_box()));
+        let (lower, upper) = objects.split_at_mut(mid);
+        let (left, left_cost) = Self::build_sah(lower, config, stats, depth + 1);
+        let (right, right_cost) = Self::build_sah(upper, config, stats, depth + 1);
+        let cost = Self::interior_cost(bbox, &left, left_cost, &right, right_cost, config);
+
+        Some((Self { left, right, bbox }, cost))
+    }
+
+    fn interior_cost(
+        bbox: Aabb,
+        left: &Arc<dyn Hittable>,
+        left_cost: f64,
+        right: &Arc<dyn Hittable>,
+        right_cost: f64,
+        config: &SahConfig,
+    ) -> f64 {
+        let area = bbox.surface_area().max(f64::MIN_POSITIVE);
+        config.traversal_cost
+            + (left.bounding_box().surface_area() * left_cost + right.bounding_box().surface_area() * right_cost) / area
+    }
+
+    fn build_leaf(
+        objects: &[Arc<dyn Hittable>],
+        config: &SahConfig,
+        stats: &mut BvhStats,
+        depth: usize,
+    ) -> (Arc<dyn Hittable>, f64) {
+        stats.record_leaf(depth, objects.len());
+        let leaf: Arc<dyn Hittable> = if objects.len() == 1 {
+            objects[0].clone()
+        } else {
+            Arc::new(HittableList::from(objects.to_vec()))
+        };
+        (leaf, config.leaf_cost(objects.len()))
+    }
+
+    fn from_objects(objects: &mut [Arc<dyn Hittable>]) -> Arc<Self> {
+        let bbox = objects
+            .iter()
+            .fold(Aabb::empty(), |bbox, object| Aabb::from_boxes(bbox, object.bounding_box()));
+
+        let (left, right): (Arc<dyn Hittable>, Arc<dyn Hittable>) = match objects.len() {
+            1 => (objects[0].clone(), objects[0].clone()),
+            2 => (objects[0].clone(), objects[1].clone()),
+            len => {
+                let axis = bbox.longest_axis();
+                let mid = len / 2;
+                objects.select_nth_unstable_by(mid, |a, b| {
+                    let a = a.bounding_box().axis_interval(axis);
+                    let b = b.bounding_box().axis_interval(axis);
+                    (a.min + a.max).total_cmp(&(b.min + b.max))
+                });
+                let (lower, upper) = objects.split_at_mut(mid);
+                (Self::from_objects(lower), Self::from_objects(upper))
+            }
+        };
+
+        Arc::new(Self {
+            left,
+            right,
+            bbox,
+        })
+    }
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, ray: Ray, t: Interval) -> Option<HitRecord> {
+        if !self.bbox.hit(ray, t) {
+            return None;
+        }
+
+        let hit_left = self.left.hit(ray, t);
+        let closest_so_far = hit_left.as_ref().map_or(t.max, |rec| rec.t);
+        let hit_right = self.right.hit(ray, (t.min, closest_so_far).into());
+
+        hit_right.or(hit_left)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::Sphere;
+    use crate::material::Lambertian;
+    use crate::utils::{rand_f64_in, Point3, Vec3};
+
+    fn random_point(extent: f64) -> Point3 {
+        Point3::from(rand_f64_in(-extent, extent), rand_f64_in(-extent, extent), rand_f64_in(-extent, extent))
+    }
+
+    fn random_spheres(count: usize) -> HittableList {
+        let material = Lambertian::from((0.5, 0.5, 0.5));
+        let mut list = HittableList::new();
+        for _ in 0..count {
+            list.add(Sphere::from(random_point(10.0), rand_f64_in(0.1, 1.0), material.clone()));
+        }
+        list
+    }
+
+    /// Fires random rays through the scene and checks the tree finds the same
+    /// closest hit as a linear scan.
+    fn matches_list(bvh: &dyn Hittable, list: &HittableList) -> bool {
+        (0..2000).all(|_| {
+            let ray = Ray::from(random_point(15.0), Vec3::random_unit_vector());
+            let t = Interval::from(0.001, f64::INFINITY);
+            match (bvh.hit(ray, t), list.hit(ray, t)) {
+                (Some(a), Some(b)) => (a.t - b.t).abs() < 1e-9,
+                (a, b) => a.is_none() && b.is_none(),
+            }
+        })
+    }
+
+    #[test]
+    fn median_split_matches_linear_list() {
+        let list = random_spheres(200);
+        assert!(matches_list(BvhNode::from(list.clone()).as_ref(), &list));
+    }
+
+    #[test]
+    fn sah_matches_linear_list() {
+        let list = random_spheres(200);
+        let (bvh, stats) = BvhNode::with_sah(list.clone(), SahConfig::default());
+        assert!(stats.sah_cost > 0.0);
+        assert!(matches_list(bvh.as_ref(), &list));
+        // The root is the top split itself, so neither child is left empty.
+        assert!(bvh.right.bounding_box().surface_area() > 0.0);
+    }
+
+    #[test]
+    fn handles_single_object_and_empty_lists() {
+        let single = random_spheres(1);
+        assert!(matches_list(BvhNode::from(single.clone()).as_ref(), &single));
+        assert!(matches_list(BvhNode::with_sah(single.clone(), SahConfig::default()).0.as_ref(), &single));
+
+        let empty = HittableList::new();
+        assert!(matches_list(BvhNode::from(empty.clone()).as_ref(), &empty));
+    }
+}
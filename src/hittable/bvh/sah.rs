@@ -0,0 +1,173 @@
+use std::fmt::{Display, Formatter};
+use crate::utils::{Aabb, Point3};
+
+#[derive(Copy, Clone)]
+pub struct SahConfig {
+    pub traversal_cost: f64,
+    pub intersection_cost: f64,
+    pub max_leaf_size: usize,
+    pub bins: usize,
+}
+
+impl SahConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(traversal_cost: f64, intersection_cost: f64, max_leaf_size: usize, bins: usize) -> Self {
+        Self {
+            traversal_cost,
+            intersection_cost,
+            max_leaf_size: max_leaf_size.max(1),
+            bins: bins.max(2),
+        }
+    }
+
+    pub fn leaf_cost(&self, count: usize) -> f64 {
+        count as f64 * self.intersection_cost
+    }
+}
+
+impl Default for SahConfig {
+    fn default() -> Self {
+        Self {
+            traversal_cost: 1.0,
+            intersection_cost: 1.0,
+            max_leaf_size: 4,
+            bins: 12,
+        }
+    }
+}
+
+#[derive(Default, Copy, Clone)]
+pub struct BvhStats {
+    pub node_count: usize,
+    pub leaf_count: usize,
+    pub primitive_count: usize,
+    pub max_depth: usize,
+    pub sah_cost: f64,
+}
+
+impl BvhStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record_node(&mut self, depth: usize) {
+        self.node_count += 1;
+        self.max_depth = self.max_depth.max(depth);
+    }
+
+    pub(crate) fn record_leaf(&mut self, depth: usize, primitives: usize) {
+        self.leaf_count += 1;
+        self.primitive_count += primitives;
+        self.max_depth = self.max_depth.max(depth);
+    }
+}
+
+impl Display for BvhStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} nodes, {} leaves, {} primitives, depth {}, SAH cost {:.3}",
+            self.node_count, self.leaf_count, self.primitive_count, self.max_depth, self.sah_cost
+        )
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Bin {
+    bbox: Aabb,
+    count: usize,
+}
+
+/// Chooses a binned SAH split for `items`, reordering them so the left child
+/// is `items[..mid]`. Returns `None` when a leaf is cheaper and allowed.
+pub(crate) fn partition<T>(items: &mut [T], bbox_of: impl Fn(&T) -> Aabb, config: &SahConfig) -> Option<usize> {
+    let count = items.len();
+    if count <= 1 {
+        return None;
+    }
+
+    let bbox = items.iter().fold(Aabb::empty(), |bbox, item| Aabb::from_boxes(bbox, bbox_of(item)));
+    let centroids = items.iter().fold(Aabb::empty(), |bounds, item| {
+        let c = bbox_of(item).centroid();
+        Aabb::from_boxes(bounds, Aabb::from_points(c, c))
+    });
+
+    let leaf_cost = config.leaf_cost(count);
+    let parent_area = bbox.surface_area();
+    let bins = config.bins.max(2);
+    let mut best: Option<(usize, usize, f64)> = None;
+
+    for axis in 0..3 {
+        let extent = centroids.axis_interval(axis);
+        if extent.size() <= 0.0 {
+            continue;
+        }
+
+        let mut bin_data = vec![Bin { bbox: Aabb::empty(), count: 0 }; bins];
+        for item in items.iter() {
+            let item_box = bbox_of(item);
+            let bin = &mut bin_data[bin_index(item_box.centroid(), axis, &centroids, bins)];
+            bin.bbox = Aabb::from_boxes(bin.bbox, item_box);
+            bin.count += 1;
+        }
+
+        let mut right_area = vec![0.0; bins];
+        let mut right_count = vec![0; bins];
+        let mut accumulated = Bin { bbox: Aabb::empty(), count: 0 };
+        for i in (1..bins).rev() {
+            accumulated.bbox = Aabb::from_boxes(accumulated.bbox, bin_data[i].bbox);
+            accumulated.count += bin_data[i].count;
+            right_area[i] = accumulated.bbox.surface_area();
+            right_count[i] = accumulated.count;
+        }
+
+        let mut accumulated = Bin { bbox: Aabb::empty(), count: 0 };
+        for split in 1..bins {
+            accumulated.bbox = Aabb::from_boxes(accumulated.bbox, bin_data[split - 1].bbox);
+            accumulated.count += bin_data[split - 1].count;
+            if accumulated.count == 0 || right_count[split] == 0 {
+                continue;
+            }
+
+            let cost = config.traversal_cost
+                + config.intersection_cost
+                    * (accumulated.bbox.surface_area() * accumulated.count as f64
+                        + right_area[split] * right_count[split] as f64)
+                    / parent_area.max(f64::MIN_POSITIVE);
+
+            if best.is_none_or(|(_, _, best_cost)| cost < best_cost) {
+                best = Some((axis, split, cost));
+            }
+        }
+    }
+
+    match best {
+        Some((_, _, cost)) if count <= config.max_leaf_size && cost >= leaf_cost => None,
+        Some((axis, split, _)) => {
+            let mut mid = 0;
+            for i in 0..count {
+                if bin_index(bbox_of(&items[i]).centroid(), axis, &centroids, bins) < split {
+                    items.swap(i, mid);
+                    mid += 1;
+                }
+            }
+            Some(mid)
+        }
+        None if count <= config.max_leaf_size => None,
+        None => Some(count / 2),
+    }
+}
+
+fn bin_index(centroid: Point3, axis: usize, centroids: &Aabb, bins: usize) -> usize {
+    let extent = centroids.axis_interval(axis);
+    let value = match axis {
+        1 => centroid.y,
+        2 => centroid.z,
+        _ => centroid.x,
+    };
+    let offset = (value - extent.min) / extent.size();
+    ((offset * bins as f64) as usize).min(bins - 1)
+}
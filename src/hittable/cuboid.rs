@@ -0,0 +1,62 @@
+use std::sync::Arc;
+use crate::hittable::{HitRecord, Hittable, HittableList, Quad};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::utils::{Aabb, Interval, Point3, Vec3};
+
+/// Axis-aligned box between two opposite corners, built from six outward
+/// facing quads.
+#[derive(Clone)]
+pub struct Cuboid {
+    sides: HittableList,
+}
+
+impl Cuboid {
+    pub fn from(a: impl Into<Point3>, b: impl Into<Point3>, material: Arc<dyn Material>) -> Arc<Self> {
+        let a = a.into();
+        let b = b.into();
+        let min = Point3::from(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z));
+        let max = Point3::from(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z));
+
+        let dx = Vec3::from_x(max.x - min.x);
+        let dy = Vec3::from_y(max.y - min.y);
+        let dz = Vec3::from_z(max.z - min.z);
+
+        Arc::new(Self {
+            sides: HittableList::from(vec![
+                Quad::from((min.x, min.y, max.z), dx, dy, material.clone()),
+                Quad::from((max.x, min.y, max.z), -dz, dy, material.clone()),
+                Quad::from((max.x, min.y, min.z), -dx, dy, material.clone()),
+                Quad::from((min.x, min.y, min.z), dz, dy, material.clone()),
+                Quad::from((min.x, max.y, max.z), dx, -dz, material.clone()),
+                Quad::from((min.x, min.y, min.z), dx, dz, material),
+            ]),
+        })
+    }
+
+    pub fn sides(&self) -> &HittableList {
+        &self.sides
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, ray: Ray, t: Interval) -> Option<HitRecord> {
+        self.sides.hit(ray, t)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.sides.bounding_box()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        self.sides.pdf_value(origin, direction, time)
+    }
+
+    fn random(&self, origin: Point3, time: f64) -> Vec3 {
+        self.sides.random(origin, time)
+    }
+
+    fn supports_sampling(&self) -> bool {
+        true
+    }
+}
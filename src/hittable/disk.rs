@@ -0,0 +1,99 @@
+use std::sync::Arc;
+use crate::hittable::{area_pdf, HitRecord, Hittable};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::utils::{Aabb, Interval, Point3, Vec3};
+
+/// Flat circle of `radius` around `center`, facing along `normal`.
+#[derive(Clone)]
+pub struct Disk {
+    pub center: Point3,
+    pub normal: Vec3,
+    pub radius: f64,
+    pub material: Arc<dyn Material>,
+    tangent: Vec3,
+    bitangent: Vec3,
+    bbox: Aabb,
+}
+
+impl Disk {
+    pub fn from(center: impl Into<Point3>, normal: impl Into<Vec3>, radius: f64, material: Arc<dyn Material>) -> Arc<Self> {
+        let center = center.into();
+        let normal = Vec3::unit_vector(normal.into());
+        let radius = radius.max(0.0);
+        let (tangent, bitangent) = Vec3::orthonormal_basis(normal);
+
+        // Half-extent of the disk along each world axis.
+        let extent = |n: f64| radius * (1.0 - n * n).max(0.0).sqrt();
+        let half = Vec3::from(extent(normal.x), extent(normal.y), extent(normal.z));
+
+        Arc::new(Self {
+            center,
+            normal,
+            radius,
+            material,
+            tangent,
+            bitangent,
+            bbox: Aabb::from_points(center - half, center + half).pad_to_minimums(0.0001),
+        })
+    }
+
+    pub fn area(&self) -> f64 {
+        crate::utils::fPI * self.radius * self.radius
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: Ray, t: Interval) -> Option<HitRecord> {
+        let denominator = Vec3::dot(self.normal, ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let root = Vec3::dot(self.normal, self.center - ray.origin) / denominator;
+        if !t.contains(root) {
+            return None;
+        }
+
+        let p = ray.at(root);
+        let offset = p - self.center;
+        if offset.length_squared() > self.radius * self.radius {
+            return None;
+        }
+
+        // Planar projection onto the disk's bounding square.
+        let u = 0.5 + 0.5 * Vec3::dot(offset, self.tangent) / self.radius;
+        let v = 0.5 + 0.5 * Vec3::dot(offset, self.bitangent) / self.radius;
+
+        Some(HitRecord::from(
+            p,
+            root,
+            (u, v),
+            ray,
+            self.material.clone(),
+            self.normal,
+        )
+        .with_uv_density(0.5 / self.radius))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        let ray = Ray::with_time(origin, direction, time);
+        let Some(rec) = self.hit(ray, Interval::from(0.001, f64::INFINITY)) else {
+            return 0.0;
+        };
+        area_pdf(direction, rec.t, self.normal, self.area())
+    }
+
+    fn random(&self, origin: Point3, _time: f64) -> Vec3 {
+        let p = Vec3::random_in_unit_disk();
+        self.center + self.radius * (p.x * self.tangent + p.y * self.bitangent) - origin
+    }
+
+    fn supports_sampling(&self) -> bool {
+        true
+    }
+}
@@ -1,32 +1,52 @@
 use crate::hittable::{HitRecord, Hittable};
 use crate::ray::Ray;
-use std::rc::Rc;
-use crate::utils::Interval;
+use std::sync::Arc;
+use crate::utils::{rand_f64, Aabb, Interval, Point3, Vec3};
 
 #[derive(Default, Clone)]
 pub struct HittableList {
-    objects: Vec<Rc<dyn Hittable>>,
+    objects: Vec<Arc<dyn Hittable>>,
+    bbox: Aabb,
 }
 
 impl HittableList {
     pub fn new() -> Self {
         Self {
             objects: Vec::new(),
+            bbox: Aabb::empty(),
         }
     }
 
-    pub fn from(objects: Vec<Rc<dyn Hittable>>) -> Self {
+    pub fn from(objects: Vec<Arc<dyn Hittable>>) -> Self {
+        let bbox = objects
+            .iter()
+            .fold(Aabb::empty(), |bbox, object| Aabb::from_boxes(bbox, object.bounding_box()));
         Self {
-            objects
+            objects,
+            bbox,
         }
     }
 
-    pub fn add(&mut self, object: Rc<dyn Hittable>) {
+    pub fn add(&mut self, object: Arc<dyn Hittable>) {
+        self.bbox = Aabb::from_boxes(self.bbox, object.bounding_box());
         self.objects.push(object);
     }
 
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.bbox = Aabb::empty();
+    }
+
+    pub fn objects(&self) -> &[Arc<dyn Hittable>] {
+        &self.objects
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
     }
 }
 
@@ -44,4 +64,30 @@ impl Hittable for HittableList {
 
         best_hit
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Picks one object uniformly, so the density is the average of theirs.
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self.objects.iter().map(|object| object.pdf_value(origin, direction, time)).sum();
+        sum / self.objects.len() as f64
+    }
+
+    fn random(&self, origin: Point3, time: f64) -> Vec3 {
+        if self.objects.is_empty() {
+            return Vec3::unit_x();
+        }
+        let index = ((rand_f64() * self.objects.len() as f64) as usize).min(self.objects.len() - 1);
+        self.objects[index].random(origin, time)
+    }
+
+    /// Only when every object can be sampled, since `random` picks any of them.
+    fn supports_sampling(&self) -> bool {
+        !self.objects.is_empty() && self.objects.iter().all(|object| object.supports_sampling())
+    }
 }
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+use crate::hittable::{area_pdf, HitRecord, Hittable};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::utils::{rand_f64, Aabb, Interval, Point3, Vec3};
+
+/// Parallelogram spanned by the edges `u` and `v` from the corner `q`.
+#[derive(Clone)]
+pub struct Quad {
+    pub q: Point3,
+    pub u: Vec3,
+    pub v: Vec3,
+    pub material: Arc<dyn Material>,
+    normal: Vec3,
+    d: f64,
+    w: Vec3,
+    bbox: Aabb,
+}
+
+impl Quad {
+    pub fn from(q: impl Into<Point3>, u: impl Into<Vec3>, v: impl Into<Vec3>, material: Arc<dyn Material>) -> Arc<Self> {
+        let q = q.into();
+        let u = u.into();
+        let v = v.into();
+        let n = Vec3::cross(u, v);
+        let normal = Vec3::unit_vector(n);
+        let diagonal = Aabb::from_points(q, q + u + v);
+        let other_diagonal = Aabb::from_points(q + u, q + v);
+        Arc::new(Self {
+            q,
+            u,
+            v,
+            material,
+            normal,
+            d: Vec3::dot(normal, q),
+            w: n / Vec3::dot(n, n),
+            bbox: Aabb::from_boxes(diagonal, other_diagonal).pad_to_minimums(0.0001),
+        })
+    }
+
+    pub fn area(&self) -> f64 {
+        Vec3::cross(self.u, self.v).length()
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: Ray, t: Interval) -> Option<HitRecord> {
+        let denominator = Vec3::dot(self.normal, ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let root = (self.d - Vec3::dot(self.normal, ray.origin)) / denominator;
+        if !t.contains(root) {
+            return None;
+        }
+
+        let p = ray.at(root);
+        let planar = p - self.q;
+        let alpha = Vec3::dot(self.w, Vec3::cross(planar, self.v));
+        let beta = Vec3::dot(self.w, Vec3::cross(self.u, planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        Some(HitRecord::from(
+            p,
+            root,
+            (alpha, beta),
+            ray,
+            self.material.clone(),
+            self.normal,
+        )
+        .with_uv_density(1.0 / self.area().sqrt()))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        let ray = Ray::with_time(origin, direction, time);
+        let Some(rec) = self.hit(ray, Interval::from(0.001, f64::INFINITY)) else {
+            return 0.0;
+        };
+        area_pdf(direction, rec.t, self.normal, self.area())
+    }
+
+    fn random(&self, origin: Point3, _time: f64) -> Vec3 {
+        self.q + rand_f64() * self.u + rand_f64() * self.v - origin
+    }
+
+    fn supports_sampling(&self) -> bool {
+        true
+    }
+}
@@ -1,34 +1,53 @@
-use std::rc::Rc;
+use std::sync::Arc;
 use crate::hittable::{HitRecord, Hittable};
-use crate::utils::Interval;
+use crate::utils::{Aabb, Interval};
 use crate::material::Material;
 use crate::ray::Ray;
-use crate::utils::{Point3, Vec3};
+use crate::utils::{fPI, rand_f64, Point3, Vec3};
 
 #[derive(Clone)]
 pub struct Sphere {
     pub center: Ray,
     pub radius: f64,
-    pub material: Rc<dyn Material>
+    pub material: Arc<dyn Material>,
+    bbox: Aabb,
 }
 
 impl Sphere {
-    pub fn from(center: impl Into<Point3>, radius: f64, material: Rc<dyn Material>) -> Rc<Self> {
-        Rc::new(Self {
-            center: Ray::from(center.into(), Vec3::zeros()),
-            radius: radius.max(0.0),
+    pub fn from(center: impl Into<Point3>, radius: f64, material: Arc<dyn Material>) -> Arc<Self> {
+        let center = center.into();
+        let radius = radius.max(0.0);
+        let radius_vector = Vec3::from(radius, radius, radius);
+        Arc::new(Self {
+            center: Ray::from(center, Vec3::zeros()),
+            radius,
             material,
+            bbox: Aabb::from_points(center - radius_vector, center + radius_vector),
         })
     }
 
-    pub fn with_time(initial_center: impl Into<Point3>, final_center: impl Into<Point3>, radius: f64, material: Rc<dyn Material>) -> Rc<Self> {
+    pub fn with_time(initial_center: impl Into<Point3>, final_center: impl Into<Point3>, radius: f64, material: Arc<dyn Material>) -> Arc<Self> {
         let initial_center = initial_center.into();
-        Rc::new(Self {
-            center: Ray::from(initial_center.clone(), final_center.into() - initial_center),
-            radius: radius.max(0.0),
+        let final_center = final_center.into();
+        let radius = radius.max(0.0);
+        let radius_vector = Vec3::from(radius, radius, radius);
+        let initial_box = Aabb::from_points(initial_center - radius_vector, initial_center + radius_vector);
+        let final_box = Aabb::from_points(final_center - radius_vector, final_center + radius_vector);
+        Arc::new(Self {
+            center: Ray::from(initial_center, final_center - initial_center),
+            radius,
             material,
+            bbox: Aabb::from_boxes(initial_box, final_box),
         })
     }
+
+    /// Maps a point on the unit sphere to (u, v), with u running around the
+    /// y axis from -x and v from the south pole to the north.
+    fn uv(p: Point3) -> (f64, f64) {
+        let theta = (-p.y).acos();
+        let phi = (-p.z).atan2(p.x) + fPI;
+        (phi / (2.0 * fPI), theta / fPI)
+    }
 }
 
 impl Hittable for Sphere {
@@ -55,12 +74,57 @@ impl Hittable for Sphere {
         }
 
         let p = ray.at(root);
+        let outward_normal = (p - current_center) / self.radius;
         Some(HitRecord::from(
             p,
             root,
+            Self::uv(outward_normal),
             ray,
             self.material.clone(),
-            (p - current_center) / self.radius,
-        ))
+            outward_normal,
+        )
+        .with_uv_density(0.5 / (fPI.sqrt() * self.radius)))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Uniform over the cone of directions the sphere subtends, or over all
+    /// directions from inside it.
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        let ray = Ray::with_time(origin, direction, time);
+        if self.hit(ray, Interval::from(0.001, f64::INFINITY)).is_none() {
+            return 0.0;
+        }
+        let distance_squared = (self.center.at(time) - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return 1.0 / (4.0 * fPI);
+        }
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        1.0 / (2.0 * fPI * (1.0 - cos_theta_max))
+    }
+
+    fn random(&self, origin: Point3, time: f64) -> Vec3 {
+        let to_center = self.center.at(time) - origin;
+        let distance_squared = to_center.length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return Vec3::random_unit_vector();
+        }
+
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let z = 1.0 + rand_f64() * (cos_theta_max - 1.0);
+        let phi = 2.0 * fPI * rand_f64();
+        let sin_theta = (1.0 - z * z).max(0.0).sqrt();
+
+        let w = Vec3::unit_vector(to_center);
+        let (u, v) = Vec3::orthonormal_basis(w);
+        sin_theta * phi.cos() * u + sin_theta * phi.sin() * v + z * w
+    }
+
+    fn supports_sampling(&self) -> bool {
+        true
     }
 }
\ No newline at end of file
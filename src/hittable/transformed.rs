@@ -0,0 +1,94 @@
+use std::sync::Arc;
+use crate::hittable::{HitRecord, Hittable};
+use crate::ray::Ray;
+use crate::utils::{Aabb, Interval, Mat4, Point3, Vec3};
+
+/// An instance of `object` placed in the world by an affine `transform`.
+/// Rays are moved into object space, so one object can be shared by many
+/// instances.
+#[derive(Clone)]
+pub struct Transformed {
+    pub object: Arc<dyn Hittable>,
+    transform: Mat4,
+    inverse: Mat4,
+    bbox: Aabb,
+}
+
+impl Transformed {
+    /// Panics if `transform` is not invertible.
+    pub fn from(object: Arc<dyn Hittable>, transform: Mat4) -> Arc<Self> {
+        let inverse = transform.inverse().expect("instance transform must be invertible");
+        let bbox = transform_box(object.bounding_box(), &transform);
+        Arc::new(Self {
+            object,
+            transform,
+            inverse,
+            bbox,
+        })
+    }
+
+    pub fn transform(&self) -> Mat4 {
+        self.transform
+    }
+}
+
+impl Hittable for Transformed {
+    fn hit(&self, ray: Ray, t: Interval) -> Option<HitRecord> {
+        // An affine map keeps the ray parameter, so `t` carries over unchanged.
+        let object_ray = Ray::with_time(
+            self.inverse.transform_point(ray.origin),
+            self.inverse.transform_vector(ray.direction),
+            ray.time,
+        )
+        .with_spread(ray.spread);
+
+        let rec = self.object.hit(object_ray, t)?;
+        Some(HitRecord {
+            p: self.transform.transform_point(rec.p),
+            normal: Vec3::unit_vector(self.inverse.transform_normal(rec.normal)),
+            ..rec
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        instance_pdf(self.object.as_ref(), &self.inverse, origin, direction, time)
+    }
+
+    fn random(&self, origin: Point3, time: f64) -> Vec3 {
+        self.transform.transform_vector(self.object.random(self.inverse.transform_point(origin), time))
+    }
+
+    fn supports_sampling(&self) -> bool {
+        self.object.supports_sampling()
+    }
+}
+
+/// Density over world directions of `object` placed by the transform whose
+/// inverse is `inverse`. The object's density over its own directions is
+/// scaled by how much the map squeezes solid angle around `direction`.
+pub(crate) fn instance_pdf(object: &dyn Hittable, inverse: &Mat4, origin: Point3, direction: Vec3, time: f64) -> f64 {
+    let local = inverse.transform_vector(Vec3::unit_vector(direction));
+    let length = local.length();
+    if length <= 0.0 {
+        return 0.0;
+    }
+    let jacobian = inverse.determinant().abs() / (length * length * length);
+    object.pdf_value(inverse.transform_point(origin), local, time) * jacobian
+}
+
+/// Bounds of the eight transformed corners of `bbox`.
+pub(crate) fn transform_box(bbox: Aabb, transform: &Mat4) -> Aabb {
+    let mut result = Aabb::empty();
+    for corner in 0..8 {
+        let x = if corner & 1 == 0 { bbox.x.min } else { bbox.x.max };
+        let y = if corner & 2 == 0 { bbox.y.min } else { bbox.y.max };
+        let z = if corner & 4 == 0 { bbox.z.min } else { bbox.z.max };
+        let p = transform.transform_point(Point3::from(x, y, z));
+        result = Aabb::from_boxes(result, Aabb::from_points(p, p));
+    }
+    result
+}
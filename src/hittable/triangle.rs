@@ -0,0 +1,214 @@
+use std::sync::Arc;
+use crate::hittable::{area_pdf, HitRecord, Hittable};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::utils::{rand_f64, Aabb, Interval, Point3, Vec3};
+
+const EPSILON: f64 = 1e-12;
+
+const DEFAULT_UVS: [(f64, f64); 3] = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)];
+
+#[derive(Clone)]
+pub struct Triangle {
+    pub vertices: [Ray; 3],
+    pub normals: Option<[Vec3; 3]>,
+    pub uvs: [(f64, f64); 3],
+    pub material: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Triangle {
+    pub fn from(a: impl Into<Point3>, b: impl Into<Point3>, c: impl Into<Point3>, material: Arc<dyn Material>) -> Arc<Self> {
+        Self::with_attributes([a.into(), b.into(), c.into()], None, None, material)
+    }
+
+    /// A triangle with optional per-vertex shading normals and texture
+    /// coordinates, both interpolated barycentrically at the hit point.
+    pub fn with_attributes(
+        vertices: [Point3; 3],
+        normals: Option<[Vec3; 3]>,
+        uvs: Option<[(f64, f64); 3]>,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        Arc::new(Self::build(vertices.map(|p| Ray::from(p, Vec3::zeros())), normals, uvs, material))
+    }
+
+    /// A triangle whose vertices move linearly from `initial_vertices` at
+    /// time 0 to `final_vertices` at time 1.
+    pub fn with_time(initial_vertices: [Point3; 3], final_vertices: [Point3; 3], material: Arc<dyn Material>) -> Arc<Self> {
+        let vertices = [0, 1, 2].map(|i| Ray::from(initial_vertices[i], final_vertices[i] - initial_vertices[i]));
+        Arc::new(Self::build(vertices, None, None, material))
+    }
+
+    fn build(vertices: [Ray; 3], normals: Option<[Vec3; 3]>, uvs: Option<[(f64, f64); 3]>, material: Arc<dyn Material>) -> Self {
+        let bounds_at = |time: f64| {
+            let [a, b, c] = vertices.map(|vertex| vertex.at(time));
+            Aabb::from_boxes(Aabb::from_points(a, b), Aabb::from_points(c, c))
+        };
+        Self {
+            vertices,
+            normals: normals.map(|normals| normals.map(Vec3::unit_vector)),
+            uvs: uvs.unwrap_or(DEFAULT_UVS),
+            material,
+            bbox: Aabb::from_boxes(bounds_at(0.0), bounds_at(1.0)).pad_to_minimums(0.0001),
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: Ray, t: Interval) -> Option<HitRecord> {
+        let [v0, v1, v2] = self.vertices.map(|vertex| vertex.at(ray.time));
+        let (root, b1, b2) = intersect([v0, v1, v2], ray, t)?;
+
+        let b0 = 1.0 - b1 - b2;
+        let [uv0, uv1, uv2] = self.uvs;
+        let uv = (
+            b0 * uv0.0 + b1 * uv1.0 + b2 * uv2.0,
+            b0 * uv0.1 + b1 * uv1.1 + b2 * uv2.1,
+        );
+
+        let rec = HitRecord::from(
+            ray.at(root),
+            root,
+            uv,
+            ray,
+            self.material.clone(),
+            Vec3::unit_vector(Vec3::cross(v1 - v0, v2 - v0)),
+        )
+        .with_uv_density(uv_density([v0, v1, v2], self.uvs));
+
+        Some(match self.normals {
+            Some([n0, n1, n2]) => rec.with_shading_normal(Vec3::unit_vector(b0 * n0 + b1 * n1 + b2 * n2)),
+            None => rec,
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        let [v0, v1, v2] = self.vertices.map(|vertex| vertex.at(time));
+        let ray = Ray::with_time(origin, direction, time);
+        let Some((root, _, _)) = intersect([v0, v1, v2], ray, Interval::from(0.001, f64::INFINITY)) else {
+            return 0.0;
+        };
+        let cross = Vec3::cross(v1 - v0, v2 - v0);
+        area_pdf(direction, root, Vec3::unit_vector(cross), 0.5 * cross.length())
+    }
+
+    fn random(&self, origin: Point3, time: f64) -> Vec3 {
+        random_point(self.vertices.map(|vertex| vertex.at(time))) - origin
+    }
+
+    fn supports_sampling(&self) -> bool {
+        true
+    }
+}
+
+/// A point spread uniformly over the triangle's area.
+pub(crate) fn random_point([v0, v1, v2]: [Point3; 3]) -> Point3 {
+    let s = rand_f64().sqrt();
+    let t = rand_f64();
+    (1.0 - s) * v0 + s * (1.0 - t) * v1 + s * t * v2
+}
+
+/// UV units per world unit across a triangle, from the ratio of its areas in
+/// UV and world space.
+pub(crate) fn uv_density([v0, v1, v2]: [Point3; 3], [uv0, uv1, uv2]: [(f64, f64); 3]) -> f64 {
+    let world_area = Vec3::cross(v1 - v0, v2 - v0).length();
+    let uv_area = ((uv1.0 - uv0.0) * (uv2.1 - uv0.1) - (uv2.0 - uv0.0) * (uv1.1 - uv0.1)).abs();
+    if world_area > 0.0 {
+        (uv_area / world_area).sqrt()
+    } else {
+        0.0
+    }
+}
+
+/// Möller–Trumbore ray/triangle test, returning the ray parameter and the
+/// barycentric weights of the second and third vertices.
+pub(crate) fn intersect([v0, v1, v2]: [Point3; 3], ray: Ray, t: Interval) -> Option<(f64, f64, f64)> {
+    let edge1 = v1 - v0;
+    let edge2 = v2 - v0;
+
+    let pvec = Vec3::cross(ray.direction, edge2);
+    let determinant = Vec3::dot(edge1, pvec);
+    if determinant.abs() < EPSILON {
+        return None;
+    }
+    let inverse_determinant = 1.0 / determinant;
+
+    let tvec = ray.origin - v0;
+    let b1 = Vec3::dot(tvec, pvec) * inverse_determinant;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let qvec = Vec3::cross(tvec, edge1);
+    let b2 = Vec3::dot(ray.direction, qvec) * inverse_determinant;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let root = Vec3::dot(edge2, qvec) * inverse_determinant;
+    if !t.surrounds(root) {
+        return None;
+    }
+
+    Some((root, b1, b2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+
+    fn unit_triangle() -> Arc<Triangle> {
+        Triangle::from((0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (0.0, 1.0, 0.0), Lambertian::from((0.5, 0.5, 0.5)))
+    }
+
+    fn everywhere() -> Interval {
+        Interval::from(0.001, f64::INFINITY)
+    }
+
+    #[test]
+    fn hit_reports_distance_and_barycentric_uv() {
+        let ray = Ray::from((0.25, 0.5, 2.0), (0.0, 0.0, -1.0));
+        let rec = unit_triangle().hit(ray, everywhere()).expect("ray should hit");
+        assert!((rec.t - 2.0).abs() < 1e-12);
+        assert!((rec.u - 0.25).abs() < 1e-12 && (rec.v - 0.5).abs() < 1e-12);
+        assert!(rec.front_face);
+        assert!((rec.normal - Vec3::from(0.0, 0.0, 1.0)).length() < 1e-12);
+    }
+
+    #[test]
+    fn uvs_are_interpolated_from_vertices() {
+        let triangle = Triangle::with_attributes(
+            [Point3::from(0.0, 0.0, 0.0), Point3::from(1.0, 0.0, 0.0), Point3::from(0.0, 1.0, 0.0)],
+            None,
+            Some([(0.5, 0.5), (1.0, 0.5), (0.5, 1.0)]),
+            Lambertian::from((0.5, 0.5, 0.5)),
+        );
+        let ray = Ray::from((0.2, 0.4, -1.0), (0.0, 0.0, 1.0));
+        let rec = triangle.hit(ray, everywhere()).expect("ray should hit");
+        assert!((rec.u - 0.6).abs() < 1e-12 && (rec.v - 0.7).abs() < 1e-12);
+        assert!(!rec.front_face);
+    }
+
+    #[test]
+    fn misses_outside_parallel_and_behind() {
+        let triangle = unit_triangle();
+        let outside = Ray::from((0.75, 0.75, 1.0), (0.0, 0.0, -1.0));
+        let parallel = Ray::from((-1.0, 0.25, 0.0), (1.0, 0.0, 0.0));
+        let behind = Ray::from((0.25, 0.25, 1.0), (0.0, 0.0, 1.0));
+        assert!(triangle.hit(outside, everywhere()).is_none());
+        assert!(triangle.hit(parallel, everywhere()).is_none());
+        assert!(triangle.hit(behind, everywhere()).is_none());
+    }
+
+    #[test]
+    fn respects_the_interval() {
+        let ray = Ray::from((0.25, 0.25, 2.0), (0.0, 0.0, -1.0));
+        assert!(unit_triangle().hit(ray, Interval::from(0.001, 1.5)).is_none());
+    }
+}
@@ -0,0 +1,350 @@
+use std::sync::Arc;
+use crate::color::Color;
+use crate::hittable::bvh::partition;
+use crate::hittable::triangle::{intersect, random_point, uv_density};
+use crate::hittable::{area_pdf, HitRecord, Hittable, SahConfig};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::utils::{rand_f64, Aabb, Distribution1D, Interval, Point3, Vec3};
+
+/// Node of the mesh's flattened BVH. Leaves own `count` triangles starting at
+/// `start`; interior nodes keep their left child at the next index and their
+/// right child at `start`, and record along which axis, and in which order,
+/// the children's centroids are separated.
+#[derive(Copy, Clone)]
+struct MeshNode {
+    bbox: Aabb,
+    start: u32,
+    count: u32,
+    axis: u8,
+    left_is_lower: bool,
+}
+
+/// Triangles sharing position, normal, UV and color buffers through an index
+/// buffer, intersected through a private BVH rather than one `Hittable` per
+/// triangle.
+pub struct TriangleMesh {
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<(f64, f64)>,
+    colors: Vec<Color>,
+    indices: Vec<[u32; 3]>,
+    material: Arc<dyn Material>,
+    nodes: Vec<MeshNode>,
+    /// Triangles weighted by area, for sampling the mesh as a light.
+    areas: Distribution1D,
+    area: f64,
+}
+
+impl TriangleMesh {
+    pub fn from(positions: Vec<Point3>, indices: Vec<[u32; 3]>, material: Arc<dyn Material>) -> Arc<Self> {
+        Self::with_attributes(positions, None, None, None, indices, material)
+    }
+
+    /// A mesh with optional per-vertex shading normals, texture coordinates
+    /// and colors, which must have one entry per position when present.
+    pub fn with_attributes(
+        positions: Vec<Point3>,
+        normals: Option<Vec<Vec3>>,
+        uvs: Option<Vec<(f64, f64)>>,
+        colors: Option<Vec<Color>>,
+        indices: Vec<[u32; 3]>,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        let normals = normals.unwrap_or_default();
+        let uvs = uvs.unwrap_or_default();
+        let colors = colors.unwrap_or_default();
+        assert!(normals.is_empty() || normals.len() == positions.len(), "mesh needs one normal per vertex");
+        assert!(uvs.is_empty() || uvs.len() == positions.len(), "mesh needs one UV per vertex");
+        assert!(colors.is_empty() || colors.len() == positions.len(), "mesh needs one color per vertex");
+        assert!(
+            indices.iter().flatten().all(|&i| (i as usize) < positions.len()),
+            "mesh index out of range"
+        );
+
+        let mut mesh = Self {
+            positions,
+            normals: normals.into_iter().map(Vec3::unit_vector).collect(),
+            uvs,
+            colors,
+            indices,
+            material,
+            nodes: Vec::new(),
+            areas: Distribution1D::new(),
+            area: 0.0,
+        };
+        mesh.build_bvh(&SahConfig::default());
+        mesh.build_areas();
+        Arc::new(mesh)
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    fn triangle(&self, index: usize) -> [Point3; 3] {
+        self.indices[index].map(|i| self.positions[i as usize])
+    }
+
+    fn triangle_box(&self, index: usize) -> Aabb {
+        let [a, b, c] = self.triangle(index);
+        Aabb::from_boxes(Aabb::from_points(a, b), Aabb::from_points(c, c)).pad_to_minimums(0.0001)
+    }
+
+    /// Indexes triangle areas after the BVH has settled the triangle order.
+    fn build_areas(&mut self) {
+        if self.indices.is_empty() {
+            return;
+        }
+        let areas: Vec<f64> = (0..self.indices.len())
+            .map(|i| {
+                let [a, b, c] = self.triangle(i);
+                0.5 * Vec3::cross(b - a, c - a).length()
+            })
+            .collect();
+        self.area = areas.iter().sum();
+        self.areas = Distribution1D::from(areas);
+    }
+
+    /// Builds the BVH with binned SAH splits, reordering the index buffer so
+    /// every leaf refers to a contiguous run of triangles.
+    fn build_bvh(&mut self, config: &SahConfig) {
+        let mut items: Vec<(Aabb, u32)> = (0..self.indices.len())
+            .map(|i| (self.triangle_box(i), i as u32))
+            .collect();
+        self.nodes.clear();
+        if items.is_empty() {
+            return;
+        }
+        self.build_node(&mut items, 0, config);
+        self.indices = items.iter().map(|&(_, i)| self.indices[i as usize]).collect();
+    }
+
+    fn build_node(&mut self, items: &mut [(Aabb, u32)], start: usize, config: &SahConfig) {
+        let bbox = items.iter().fold(Aabb::empty(), |bbox, item| Aabb::from_boxes(bbox, item.0));
+        let node = self.nodes.len();
+        self.nodes.push(MeshNode {
+            bbox,
+            start: start as u32,
+            count: items.len() as u32,
+            axis: 0,
+            left_is_lower: true,
+        });
+
+        let Some(mid) = partition(items, |item| item.0, config) else {
+            return;
+        };
+
+        let (lower, upper) = items.split_at_mut(mid);
+        let left_centroid = lower.iter().fold(Aabb::empty(), |b, item| Aabb::from_boxes(b, item.0)).centroid();
+        let right_centroid = upper.iter().fold(Aabb::empty(), |b, item| Aabb::from_boxes(b, item.0)).centroid();
+        let separation = right_centroid - left_centroid;
+        let separation = [separation.x, separation.y, separation.z];
+        let axis = separation
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .map_or(0, |(axis, _)| axis);
+
+        self.build_node(lower, start, config);
+        let right = self.nodes.len();
+        self.build_node(upper, start + mid, config);
+
+        self.nodes[node] = MeshNode {
+            bbox,
+            start: right as u32,
+            count: 0,
+            axis: axis as u8,
+            left_is_lower: separation[axis] >= 0.0,
+        };
+    }
+}
+
+impl TriangleMesh {
+    /// Nearest triangle along `ray`, with the ray parameter and the
+    /// barycentric weights of its second and third vertices.
+    fn closest_hit(&self, ray: Ray, t: Interval) -> Option<(usize, f64, f64, f64)> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let direction = [ray.direction.x, ray.direction.y, ray.direction.z];
+        let mut closest: Option<(usize, f64, f64, f64)> = None;
+        let mut closest_so_far = t.max;
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0usize);
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.bbox.hit(ray, Interval::from(t.min, closest_so_far)) {
+                continue;
+            }
+
+            if node.count > 0 {
+                for triangle in node.start as usize..(node.start + node.count) as usize {
+                    if let Some((root, b1, b2)) = intersect(self.triangle(triangle), ray, Interval::from(t.min, closest_so_far)) {
+                        closest_so_far = root;
+                        closest = Some((triangle, root, b1, b2));
+                    }
+                }
+            } else if (direction[node.axis as usize] >= 0.0) == node.left_is_lower {
+                stack.push(node.start as usize);
+                stack.push(index + 1);
+            } else {
+                stack.push(index + 1);
+                stack.push(node.start as usize);
+            }
+        }
+
+        closest
+    }
+
+    /// Calls `visit` with the index and ray parameter of every triangle the
+    /// ray crosses within `t`, in no particular order.
+    fn for_each_hit(&self, ray: Ray, t: Interval, mut visit: impl FnMut(usize, f64)) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0usize);
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.bbox.hit(ray, t) {
+                continue;
+            }
+
+            if node.count > 0 {
+                for triangle in node.start as usize..(node.start + node.count) as usize {
+                    if let Some((root, _, _)) = intersect(self.triangle(triangle), ray, t) {
+                        visit(triangle, root);
+                    }
+                }
+            } else {
+                stack.push(index + 1);
+                stack.push(node.start as usize);
+            }
+        }
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: Ray, t: Interval) -> Option<HitRecord> {
+        let (triangle, root, b1, b2) = self.closest_hit(ray, t)?;
+        let [i0, i1, i2] = self.indices[triangle].map(|i| i as usize);
+        let [v0, v1, v2] = self.triangle(triangle);
+        let b0 = 1.0 - b1 - b2;
+
+        let [uv0, uv1, uv2] = if self.uvs.is_empty() {
+            [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]
+        } else {
+            [self.uvs[i0], self.uvs[i1], self.uvs[i2]]
+        };
+        let uv = (
+            b0 * uv0.0 + b1 * uv1.0 + b2 * uv2.0,
+            b0 * uv0.1 + b1 * uv1.1 + b2 * uv2.1,
+        );
+
+        let rec = HitRecord::from(
+            ray.at(root),
+            root,
+            uv,
+            ray,
+            self.material.clone(),
+            Vec3::unit_vector(Vec3::cross(v1 - v0, v2 - v0)),
+        )
+        .with_uv_density(uv_density([v0, v1, v2], [uv0, uv1, uv2]));
+
+        let rec = if self.normals.is_empty() {
+            rec
+        } else {
+            let normal = b0 * self.normals[i0] + b1 * self.normals[i1] + b2 * self.normals[i2];
+            rec.with_shading_normal(Vec3::unit_vector(normal))
+        };
+
+        Some(if self.colors.is_empty() {
+            rec
+        } else {
+            rec.with_vertex_color(b0 * self.colors[i0] + b1 * self.colors[i1] + b2 * self.colors[i2])
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.nodes.first().map_or(Aabb::empty(), |node| node.bbox)
+    }
+
+    /// Uniform over the mesh's total area. `random` can pick a point on any
+    /// triangle, so every triangle `direction` crosses adds to the density,
+    /// not just the nearest one.
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        let ray = Ray::with_time(origin, direction, time);
+        let mut pdf = 0.0;
+        self.for_each_hit(ray, Interval::from(0.001, f64::INFINITY), |triangle, root| {
+            let [v0, v1, v2] = self.triangle(triangle);
+            pdf += area_pdf(direction, root, Vec3::unit_vector(Vec3::cross(v1 - v0, v2 - v0)), self.area);
+        });
+        pdf
+    }
+
+    fn random(&self, origin: Point3, _time: f64) -> Vec3 {
+        let (_, _, triangle) = self.areas.sample(rand_f64());
+        random_point(self.triangle(triangle)) - origin
+    }
+
+    fn supports_sampling(&self) -> bool {
+        self.area > 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::utils::rand_f64_in;
+
+    fn random_point(extent: f64) -> Point3 {
+        Point3::from(rand_f64_in(-extent, extent), rand_f64_in(-extent, extent), rand_f64_in(-extent, extent))
+    }
+
+    /// Small triangles scattered through a cube, none sharing vertices.
+    fn triangle_soup(count: usize) -> Arc<TriangleMesh> {
+        let positions: Vec<Point3> = (0..count)
+            .flat_map(|_| {
+                let corner = random_point(10.0);
+                [corner, corner + random_point(1.0), corner + random_point(1.0)]
+            })
+            .collect();
+        let indices = (0..count as u32).map(|i| [3 * i, 3 * i + 1, 3 * i + 2]).collect();
+        TriangleMesh::from(positions, indices, Lambertian::from((0.5, 0.5, 0.5)))
+    }
+
+    #[test]
+    fn bvh_finds_the_closest_triangle() {
+        let mesh = triangle_soup(300);
+        let t = Interval::from(0.001, f64::INFINITY);
+        for _ in 0..2000 {
+            let ray = Ray::from(random_point(15.0), Vec3::random_unit_vector());
+            let linear = (0..mesh.triangle_count())
+                .filter_map(|i| intersect(mesh.triangle(i), ray, t))
+                .map(|(root, _, _)| root)
+                .min_by(f64::total_cmp);
+            let found = mesh.closest_hit(ray, t).map(|(_, root, _, _)| root);
+            assert_eq!(found, linear);
+        }
+    }
+
+    #[test]
+    fn interior_nodes_know_which_child_is_lower() {
+        let mesh = triangle_soup(300);
+        for (index, node) in mesh.nodes.iter().enumerate().filter(|(_, node)| node.count == 0) {
+            let axis = node.axis as usize;
+            let left = mesh.nodes[index + 1].bbox.axis_interval(axis);
+            let right = mesh.nodes[node.start as usize].bbox.axis_interval(axis);
+            assert_eq!(left.min + left.max <= right.min + right.max, node.left_is_lower);
+        }
+    }
+}
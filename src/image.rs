@@ -1,5 +1,6 @@
 use image::{ImageBuffer, Rgb, RgbImage};
 use std::path::Path;
+use crate::color::{luminance, write_color, Color};
 
 #[derive(Default)]
 pub struct Image {
@@ -24,7 +25,125 @@ impl Image {
         }
     }
 
+    pub fn with_size(width: u32, height: u32) -> Self {
+        Self {
+            aspect_ratio: width as f64 / height.max(1) as f64,
+            width,
+            height,
+            buffer: RgbImage::new(width, height),
+        }
+    }
+
     pub fn save<P: AsRef<Path>>(&self, path: P) {
         self.buffer.save(path).unwrap()
     }
 }
+
+/// Running sums for the radiance samples taken through one pixel, enough to
+/// recover their mean and the variance of their luminance.
+#[derive(Default, Copy, Clone)]
+pub struct PixelStats {
+    pub sum: Color,
+    pub luminance_sum_squares: f64,
+    pub samples: u32,
+}
+
+impl PixelStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, color: Color) {
+        let l = luminance(color);
+        self.sum += color;
+        self.luminance_sum_squares += l * l;
+        self.samples += 1;
+    }
+
+    pub fn merge(&mut self, other: PixelStats) {
+        self.sum += other.sum;
+        self.luminance_sum_squares += other.luminance_sum_squares;
+        self.samples += other.samples;
+    }
+
+    pub fn mean(&self) -> Color {
+        if self.samples == 0 {
+            Color::zeros()
+        } else {
+            self.sum / self.samples as f64
+        }
+    }
+
+    /// Standard error of the mean luminance relative to the mean itself, which
+    /// keeps the threshold meaningful in both dark and bright regions.
+    pub fn relative_error(&self) -> f64 {
+        if self.samples < 2 {
+            return f64::INFINITY;
+        }
+        let n = self.samples as f64;
+        let mean = luminance(self.sum) / n;
+        let variance = ((self.luminance_sum_squares - n * mean * mean) / (n - 1.0)).max(0.0);
+        (variance / n).sqrt() / (mean + 1e-3)
+    }
+}
+
+/// Per-pixel sample statistics in linear floating point, resolved into an
+/// `Image` on demand.
+#[derive(Default, Clone)]
+pub struct AccumulationBuffer {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<PixelStats>,
+}
+
+impl AccumulationBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![PixelStats::new(); width as usize * height as usize],
+        }
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    pub fn add(&mut self, x: u32, y: u32, stats: PixelStats) {
+        let i = self.index(x, y);
+        self.pixels[i].merge(stats);
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> PixelStats {
+        self.pixels[self.index(x, y)]
+    }
+
+    pub fn samples(&self, x: u32, y: u32) -> u32 {
+        self.pixel(x, y).samples
+    }
+
+    pub fn mean(&self, x: u32, y: u32) -> Color {
+        self.pixel(x, y).mean()
+    }
+
+    pub fn resolve(&self, image: &mut Image) {
+        for (x, y, pixel) in image.buffer.enumerate_pixels_mut() {
+            write_color(pixel, self.mean(x, y));
+        }
+    }
+
+    /// Grayscale image of the per-pixel sample count, with `max_samples` white.
+    pub fn sample_count_image(&self, max_samples: u32) -> Image {
+        let mut image = Image::with_size(self.width, self.height);
+        let scale = 255.0 / max_samples.max(1) as f64;
+        for (x, y, pixel) in image.buffer.enumerate_pixels_mut() {
+            let level = (self.samples(x, y) as f64 * scale).min(255.0) as u8;
+            *pixel = Rgb([level, level, level]);
+        }
+        image
+    }
+}
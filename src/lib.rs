@@ -0,0 +1,11 @@
+pub mod camera;
+pub mod color;
+pub mod hittable;
+pub mod image;
+pub mod ray;
+pub mod utils;
+pub mod material;
+pub mod loader;
+pub mod texture;
+pub mod background;
+pub mod pdf;
@@ -0,0 +1,99 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::utils::{Point3, Vec3};
+
+mod mtl;
+mod obj;
+mod ply;
+mod stl;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    Invalid {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl LoadError {
+    pub fn io(path: impl AsRef<Path>, source: io::Error) -> Self {
+        Self::Io {
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
+
+    pub fn parse(path: impl AsRef<Path>, line: usize, message: impl Into<String>) -> Self {
+        Self::Parse {
+            path: path.as_ref().to_path_buf(),
+            line,
+            message: message.into(),
+        }
+    }
+
+    /// An error in data without line structure, such as a binary body.
+    pub fn invalid(path: impl AsRef<Path>, message: impl Into<String>) -> Self {
+        Self::Invalid {
+            path: path.as_ref().to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Parse { path, line, message } => write!(f, "{}:{}: {}", path.display(), line, message),
+            Self::Invalid { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Parse { .. } | Self::Invalid { .. } => None,
+        }
+    }
+}
+
+/// Parses the next whitespace-separated token as a number, naming `what` in
+/// the error message when it is missing or malformed.
+fn parse_next<'a, T: std::str::FromStr>(tokens: &mut impl Iterator<Item = &'a str>, what: &str) -> Result<T, String> {
+    let token = tokens.next().ok_or_else(|| format!("missing {what}"))?;
+    token.parse().map_err(|_| format!("invalid {what} '{token}'"))
+}
+
+/// Zero-area triangles, which every loader drops rather than handing them to
+/// the mesh.
+fn is_degenerate([a, b, c]: [Point3; 3]) -> bool {
+    Vec3::cross(b - a, c - a).near_zero()
+}
+
+/// Writes `contents` to a file named `name` in a per-process scratch
+/// directory, for tests that load from disk.
+#[cfg(test)]
+fn write_temp(name: &str, contents: &[u8]) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("crayfish-loader-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+pub use mtl::MtlMaterial;
+pub use obj::{ObjGroup, ObjModel};
+pub use ply::load_ply;
+pub use stl::load_stl;
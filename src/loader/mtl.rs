@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::color::Color;
+use crate::loader::{parse_next, LoadError};
+use crate::material::{Dielectric, Lambertian, Material, Metal};
+use crate::texture::{ImageTexture, SolidColor, Texture};
+
+/// One `newmtl` entry of a Wavefront material library.
+#[derive(Clone)]
+pub struct MtlMaterial {
+    pub name: String,
+    pub diffuse: Color,
+    pub specular: Color,
+    pub shininess: f64,
+    pub refraction_index: f64,
+    pub dissolve: f64,
+    pub illum: u32,
+    pub diffuse_map: Option<PathBuf>,
+}
+
+impl MtlMaterial {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Parses every material in the library at `path`, keyed by name. Texture
+    /// paths are resolved relative to the library's directory.
+    pub fn load_library(path: impl AsRef<Path>) -> Result<HashMap<String, MtlMaterial>, LoadError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| LoadError::io(path, e))?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let mut materials = HashMap::new();
+        let mut current: Option<MtlMaterial> = None;
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut tokens = line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+            let error = |message: String| LoadError::parse(path, line_number, message);
+
+            if keyword == "newmtl" {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                if name.is_empty() {
+                    return Err(error("newmtl without a name".into()));
+                }
+                if let Some(material) = current.replace(MtlMaterial::from(name)) {
+                    materials.insert(material.name.clone(), material);
+                }
+                continue;
+            }
+
+            let Some(material) = current.as_mut() else {
+                return Err(error(format!("'{keyword}' before any newmtl")));
+            };
+
+            match keyword {
+                "Kd" => material.diffuse = parse_color(&mut tokens).map_err(error)?,
+                "Ks" => material.specular = parse_color(&mut tokens).map_err(error)?,
+                "Ns" => material.shininess = parse_next(&mut tokens, "Ns exponent").map_err(error)?,
+                "Ni" => material.refraction_index = parse_next(&mut tokens, "Ni index").map_err(error)?,
+                "d" => material.dissolve = parse_next(&mut tokens, "dissolve").map_err(error)?,
+                "Tr" => material.dissolve = 1.0 - parse_next::<f64>(&mut tokens, "transparency").map_err(error)?,
+                "illum" => material.illum = parse_next(&mut tokens, "illumination model").map_err(error)?,
+                "map_Kd" => {
+                    let file = tokens.last().ok_or_else(|| error("map_Kd without a file name".into()))?;
+                    material.diffuse_map = Some(directory.join(file));
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(material) = current {
+            materials.insert(material.name.clone(), material);
+        }
+
+        Ok(materials)
+    }
+
+    /// Picks the closest of our materials: transparent or refractive models
+    /// become `Dielectric`, reflective ones `Metal` with fuzz derived from the
+    /// Phong exponent, and everything else `Lambertian`. A `map_Kd` image
+    /// replaces the diffuse color.
+    pub fn to_material(&self) -> Result<Arc<dyn Material>, LoadError> {
+        let has_specular = self.specular.x.max(self.specular.y).max(self.specular.z) > 0.0;
+        Ok(match self.illum {
+            _ if self.dissolve < 1.0 || matches!(self.illum, 4 | 6 | 7 | 9) => Dielectric::from(self.refraction_index),
+            3 | 5 | 8 if has_specular => Metal::from(self.specular, self.fuzz()),
+            3 | 5 | 8 => Metal::with_texture(self.diffuse_texture()?, self.fuzz()),
+            _ => Lambertian::with_texture(self.diffuse_texture()?),
+        })
+    }
+
+    fn diffuse_texture(&self) -> Result<Arc<dyn Texture>, LoadError> {
+        match &self.diffuse_map {
+            Some(path) => Ok(ImageTexture::load(path).map_err(|e| LoadError::invalid(path, e.to_string()))?),
+            None => Ok(SolidColor::from(self.diffuse)),
+        }
+    }
+
+    fn fuzz(&self) -> f64 {
+        (2.0 / (self.shininess.max(0.0) + 2.0)).sqrt()
+    }
+}
+
+impl Default for MtlMaterial {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            diffuse: Color::from(0.8, 0.8, 0.8),
+            specular: Color::zeros(),
+            shininess: 0.0,
+            refraction_index: 1.5,
+            dissolve: 1.0,
+            illum: 2,
+            diffuse_map: None,
+        }
+    }
+}
+
+fn parse_color<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Result<Color, String> {
+    let r: f64 = parse_next(tokens, "red component")?;
+    let mut rest = tokens.peekable();
+    if rest.peek().is_none() {
+        return Ok(Color::from(r, r, r));
+    }
+    let g = parse_next(&mut rest, "green component")?;
+    let b = parse_next(&mut rest, "blue component")?;
+    Ok(Color::from(r, g, b))
+}
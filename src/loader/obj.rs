@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use crate::hittable::{BvhNode, Hittable, HittableList, TriangleMesh};
+use crate::loader::{is_degenerate, parse_next, LoadError, MtlMaterial};
+use crate::material::Material;
+use crate::utils::{Point3, Vec3};
+
+/// Faces sharing a `g`/`o` name in an OBJ file, with one mesh per material.
+#[derive(Clone)]
+pub struct ObjGroup {
+    pub name: String,
+    pub meshes: Vec<Arc<TriangleMesh>>,
+}
+
+impl ObjGroup {
+    pub fn triangle_count(&self) -> usize {
+        self.meshes.iter().map(|mesh| mesh.triangle_count()).sum()
+    }
+}
+
+/// A Wavefront OBJ file converted to triangles, with materials taken from the
+/// MTL libraries it references.
+#[derive(Default, Clone)]
+pub struct ObjModel {
+    pub groups: Vec<ObjGroup>,
+    pub materials: HashMap<String, MtlMaterial>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+struct FaceVertex {
+    position: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+}
+
+/// Collects the faces of one group that share a material, merging corners
+/// with identical position/UV/normal indices into one mesh vertex.
+#[derive(Default)]
+struct MeshBuilder {
+    positions: Vec<Point3>,
+    uvs: Vec<Option<(f64, f64)>>,
+    normals: Vec<Option<Vec3>>,
+    indices: Vec<[u32; 3]>,
+    lookup: HashMap<FaceVertex, u32>,
+}
+
+impl MeshBuilder {
+    fn vertex(&mut self, corner: FaceVertex, positions: &[Point3], uvs: &[(f64, f64)], normals: &[Vec3]) -> u32 {
+        *self.lookup.entry(corner).or_insert_with(|| {
+            self.positions.push(positions[corner.position]);
+            self.uvs.push(corner.uv.map(|i| uvs[i]));
+            self.normals.push(corner.normal.map(|i| normals[i]));
+            (self.positions.len() - 1) as u32
+        })
+    }
+
+    /// Finishes the mesh. Normals are only kept when every vertex has one;
+    /// missing texture coordinates default to the origin.
+    fn build(self, material: Arc<dyn Material>) -> Arc<TriangleMesh> {
+        let normals = self.normals.into_iter().collect::<Option<Vec<_>>>();
+        let uvs = if self.uvs.iter().any(Option::is_some) {
+            Some(self.uvs.into_iter().map(|uv| uv.unwrap_or((0.0, 0.0))).collect())
+        } else {
+            None
+        };
+        TriangleMesh::with_attributes(self.positions, normals, uvs, None, self.indices, material)
+    }
+}
+
+/// Meshes under construction for the current group, in first-use order.
+struct GroupBuilder {
+    name: String,
+    meshes: Vec<(Arc<dyn Material>, MeshBuilder)>,
+}
+
+impl GroupBuilder {
+    fn from(name: String) -> Self {
+        Self {
+            name: if name.is_empty() { String::from("default") } else { name },
+            meshes: Vec::new(),
+        }
+    }
+
+    fn mesh(&mut self, material: &Arc<dyn Material>) -> &mut MeshBuilder {
+        let index = match self.meshes.iter().position(|(m, _)| Arc::ptr_eq(m, material)) {
+            Some(index) => index,
+            None => {
+                self.meshes.push((material.clone(), MeshBuilder::default()));
+                self.meshes.len() - 1
+            }
+        };
+        &mut self.meshes[index].1
+    }
+
+    fn build(self) -> Option<ObjGroup> {
+        let meshes: Vec<_> = self
+            .meshes
+            .into_iter()
+            .filter(|(_, builder)| !builder.indices.is_empty())
+            .map(|(material, builder)| builder.build(material))
+            .collect();
+        if meshes.is_empty() {
+            None
+        } else {
+            Some(ObjGroup {
+                name: self.name,
+                meshes,
+            })
+        }
+    }
+}
+
+impl ObjModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the OBJ at `path` into indexed meshes. Faces with more than three
+    /// vertices are triangulated as fans, and faces without a `usemtl` get
+    /// `default_material`.
+    pub fn load(path: impl AsRef<Path>, default_material: Arc<dyn Material>) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| LoadError::io(path, e))?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+
+        let mut positions: Vec<Point3> = Vec::new();
+        let mut normals: Vec<Vec3> = Vec::new();
+        let mut uvs: Vec<(f64, f64)> = Vec::new();
+
+        let mut model = Self::new();
+        let mut converted: HashMap<String, Arc<dyn Material>> = HashMap::new();
+        let mut material = default_material.clone();
+        let mut group = GroupBuilder::from(String::new());
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut tokens = line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+            let error = |message: String| LoadError::parse(path, line_number, message);
+
+            match keyword {
+                "v" => positions.push(parse_vec3(&mut tokens, "vertex").map_err(error)?),
+                "vn" => normals.push(parse_vec3(&mut tokens, "normal").map_err(error)?),
+                "vt" => {
+                    let u = parse_next(&mut tokens, "texture u").map_err(error)?;
+                    let v = match tokens.next() {
+                        Some(token) => token.parse().map_err(|_| error(format!("invalid texture v '{token}'")))?,
+                        None => 0.0,
+                    };
+                    uvs.push((u, v));
+                }
+                "f" => {
+                    let vertices = tokens
+                        .map(|token| parse_face_vertex(token, positions.len(), uvs.len(), normals.len()))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(error)?;
+                    if vertices.len() < 3 {
+                        return Err(error(format!("face has {} vertices, expected at least 3", vertices.len())));
+                    }
+
+                    let mesh = group.mesh(&material);
+                    for i in 1..vertices.len() - 1 {
+                        let corners = [vertices[0], vertices[i], vertices[i + 1]];
+                        if is_degenerate(corners.map(|corner| positions[corner.position])) {
+                            continue;
+                        }
+                        let triangle = corners.map(|corner| mesh.vertex(corner, &positions, &uvs, &normals));
+                        mesh.indices.push(triangle);
+                    }
+                }
+                "g" | "o" => {
+                    let name = tokens.collect::<Vec<_>>().join(" ");
+                    let previous = std::mem::replace(&mut group, GroupBuilder::from(name));
+                    model.groups.extend(previous.build());
+                }
+                "mtllib" => {
+                    for file in tokens {
+                        model.materials.extend(MtlMaterial::load_library(directory.join(file))?);
+                    }
+                }
+                "usemtl" => {
+                    let name = tokens.collect::<Vec<_>>().join(" ");
+                    material = match converted.get(&name) {
+                        Some(material) => material.clone(),
+                        None => {
+                            let mtl = model
+                                .materials
+                                .get(&name)
+                                .ok_or_else(|| error(format!("unknown material '{name}'")))?;
+                            let material = mtl.to_material()?;
+                            converted.insert(name, material.clone());
+                            material
+                        }
+                    };
+                }
+                _ => {}
+            }
+        }
+
+        model.groups.extend(group.build());
+
+        Ok(model)
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.groups.iter().map(ObjGroup::triangle_count).sum()
+    }
+
+    /// Collects every group's meshes under a single BVH, ready to be added to
+    /// a scene's `HittableList`.
+    pub fn into_hittable(self) -> Arc<dyn Hittable> {
+        let mut meshes = HittableList::new();
+        for mesh in self.groups.into_iter().flat_map(|group| group.meshes) {
+            meshes.add(mesh);
+        }
+        BvhNode::from(meshes)
+    }
+}
+
+fn parse_vec3<'a>(tokens: &mut impl Iterator<Item = &'a str>, what: &str) -> Result<Vec3, String> {
+    Ok(Vec3::from(
+        parse_next(tokens, &format!("{what} x"))?,
+        parse_next(tokens, &format!("{what} y"))?,
+        parse_next(tokens, &format!("{what} z"))?,
+    ))
+}
+
+/// Parses one `v`, `v/vt`, `v//vn` or `v/vt/vn` face corner, resolving
+/// 1-based and negative (relative) indices against the counts seen so far.
+fn parse_face_vertex(token: &str, positions: usize, uvs: usize, normals: usize) -> Result<FaceVertex, String> {
+    let mut parts = token.split('/');
+    let resolve = |part: Option<&str>, count: usize, what: &str| -> Result<Option<usize>, String> {
+        let Some(part) = part.filter(|part| !part.is_empty()) else {
+            return Ok(None);
+        };
+        let index: i64 = part.parse().map_err(|_| format!("invalid {what} index '{part}' in '{token}'"))?;
+        let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+        if index == 0 || resolved < 0 || resolved >= count as i64 {
+            return Err(format!("{what} index {index} out of range in '{token}'"));
+        }
+        Ok(Some(resolved as usize))
+    };
+
+    let position = resolve(parts.next(), positions, "vertex")?
+        .ok_or_else(|| format!("face corner '{token}' has no vertex index"))?;
+    let uv = resolve(parts.next(), uvs, "texture")?;
+    let normal = resolve(parts.next(), normals, "normal")?;
+
+    Ok(FaceVertex {
+        position,
+        uv,
+        normal,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::write_temp;
+    use crate::material::Lambertian;
+
+    fn load(name: &str, source: &str) -> Result<ObjModel, LoadError> {
+        ObjModel::load(write_temp(name, source.as_bytes()), Lambertian::from((0.5, 0.5, 0.5)))
+    }
+
+    #[test]
+    fn triangulates_polygons_and_splits_groups() {
+        let model = load(
+            "groups.obj",
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+             g quad\nf 1/1 2/2 3/3 4/4\n\
+             g triangle # trailing comment\nf -4 -3 -2\n",
+        )
+        .unwrap();
+
+        assert_eq!(model.triangle_count(), 3);
+        let names: Vec<&str> = model.groups.iter().map(|group| group.name.as_str()).collect();
+        assert_eq!(names, ["quad", "triangle"]);
+        assert_eq!(model.groups[0].meshes[0].vertex_count(), 4);
+    }
+
+    #[test]
+    fn reads_materials_from_mtllib() {
+        write_temp("shared.mtl", b"newmtl red\nKd 1 0 0\n");
+        let model = load("materials.obj", "mtllib shared.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl red\nf 1 2 3\n").unwrap();
+        assert!(model.materials.contains_key("red"));
+        assert_eq!(model.triangle_count(), 1);
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        let cases = [
+            ("range.obj", "v 0 0 0\nv 1 0 0\nf 1 2 3\n", 3),
+            ("short.obj", "v 0 0 0\nv 1 0 0\nf 1 2\n", 3),
+            ("number.obj", "v 0 zero 0\n", 1),
+            ("material.obj", "v 0 0 0\nusemtl missing\n", 2),
+        ];
+        for (name, source, expected) in cases {
+            match load(name, source) {
+                Err(LoadError::Parse { line, .. }) => assert_eq!(line, expected, "{name}"),
+                Err(error) => panic!("{name}: unexpected error {error}"),
+                Ok(_) => panic!("{name}: loaded without error"),
+            }
+        }
+        assert!(matches!(
+            ObjModel::load("/nonexistent/model.obj", Lambertian::from((0.5, 0.5, 0.5))),
+            Err(LoadError::Io { .. })
+        ));
+    }
+}
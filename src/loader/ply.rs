@@ -0,0 +1,527 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use crate::color::{gamma_to_linear, Color};
+use crate::hittable::TriangleMesh;
+use crate::loader::{is_degenerate, LoadError};
+use crate::material::Material;
+use crate::utils::{Point3, Vec3};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Copy, Clone)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => Self::I8,
+            "uchar" | "uint8" => Self::U8,
+            "short" | "int16" => Self::I16,
+            "ushort" | "uint16" => Self::U16,
+            "int" | "int32" => Self::I32,
+            "uint" | "uint32" => Self::U32,
+            "float" | "float32" => Self::F32,
+            "double" | "float64" => Self::F64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    /// Scale that maps the type's range onto [0, 1] for color channels.
+    fn color_scale(self) -> f64 {
+        match self {
+            Self::U8 => 1.0 / u8::MAX as f64,
+            Self::U16 => 1.0 / u16::MAX as f64,
+            Self::U32 => 1.0 / u32::MAX as f64,
+            Self::I8 => 1.0 / i8::MAX as f64,
+            Self::I16 => 1.0 / i16::MAX as f64,
+            Self::I32 => 1.0 / i32::MAX as f64,
+            Self::F32 | Self::F64 => 1.0,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+enum PropertyKind {
+    Scalar(Scalar),
+    List { count: Scalar, item: Scalar },
+}
+
+struct Property {
+    name: String,
+    kind: PropertyKind,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Reads values from the body of a PLY file in either encoding.
+enum Body<'a> {
+    Ascii { tokens: Vec<(usize, &'a str)>, next: usize },
+    Binary { data: &'a [u8], offset: usize, big_endian: bool },
+}
+
+impl Body<'_> {
+    fn read(&mut self, scalar: Scalar) -> Result<f64, String> {
+        match self {
+            Self::Ascii { tokens, next } => {
+                let (_, token) = tokens.get(*next).ok_or("unexpected end of file")?;
+                *next += 1;
+                token.parse().map_err(|_| format!("invalid number '{token}'"))
+            }
+            Self::Binary { data, offset, big_endian } => {
+                let size = scalar.size();
+                let bytes = data
+                    .get(*offset..*offset + size)
+                    .ok_or_else(|| format!("unexpected end of file at byte {offset}"))?;
+                *offset += size;
+                let mut buffer = [0u8; 8];
+                buffer[..size].copy_from_slice(bytes);
+                if *big_endian {
+                    buffer[..size].reverse();
+                }
+                Ok(match scalar {
+                    Scalar::I8 => buffer[0] as i8 as f64,
+                    Scalar::U8 => buffer[0] as f64,
+                    Scalar::I16 => i16::from_le_bytes([buffer[0], buffer[1]]) as f64,
+                    Scalar::U16 => u16::from_le_bytes([buffer[0], buffer[1]]) as f64,
+                    Scalar::I32 => i32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as f64,
+                    Scalar::U32 => u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as f64,
+                    Scalar::F32 => f32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as f64,
+                    Scalar::F64 => f64::from_le_bytes(buffer),
+                })
+            }
+        }
+    }
+
+    fn error(&self, path: &Path, message: String) -> LoadError {
+        match self {
+            Self::Ascii { tokens, next } => {
+                let line = tokens.get(next.saturating_sub(1)).map_or(0, |&(line, _)| line);
+                LoadError::parse(path, line, message)
+            }
+            Self::Binary { .. } => LoadError::invalid(path, message),
+        }
+    }
+}
+
+/// Loads an ASCII or binary PLY file as an indexed mesh. Polygons are
+/// triangulated as fans; vertex normals, texture coordinates and colors are
+/// kept when present, with colors multiplied into a `Lambertian` albedo.
+pub fn load_ply(path: impl AsRef<Path>, material: Arc<dyn Material>) -> Result<Arc<TriangleMesh>, LoadError> {
+    let path = path.as_ref();
+    let data = fs::read(path).map_err(|e| LoadError::io(path, e))?;
+    let (format, elements, header_lines, body_start) = parse_header(path, &data)?;
+
+    let mut body = match format {
+        Format::Ascii => {
+            let text = std::str::from_utf8(&data[body_start..])
+                .map_err(|_| LoadError::invalid(path, "ASCII body is not valid UTF-8"))?;
+            let tokens = text
+                .lines()
+                .enumerate()
+                .flat_map(|(index, line)| line.split_whitespace().map(move |token| (header_lines + index + 1, token)))
+                .collect();
+            Body::Ascii { tokens, next: 0 }
+        }
+        Format::BinaryLittleEndian | Format::BinaryBigEndian => Body::Binary {
+            data: &data[body_start..],
+            offset: 0,
+            big_endian: format == Format::BinaryBigEndian,
+        },
+    };
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut colors = Vec::new();
+    let mut indices = Vec::new();
+
+    for element in &elements {
+        match element.name.as_str() {
+            "vertex" => read_vertices(element, &mut body, &mut positions, &mut normals, &mut uvs, &mut colors)
+                .map_err(|message| body.error(path, message))?,
+            "face" => read_faces(element, &mut body, &mut indices).map_err(|message| body.error(path, message))?,
+            _ => skip_element(element, &mut body).map_err(|message| body.error(path, message))?,
+        }
+    }
+
+    if let Some(&index) = indices.iter().flatten().find(|&&i| i as usize >= positions.len()) {
+        return Err(LoadError::invalid(path, format!("face refers to vertex {index} of {}", positions.len())));
+    }
+    indices.retain(|triangle| !is_degenerate(triangle.map(|i| positions[i as usize])));
+    if indices.is_empty() {
+        return Err(LoadError::invalid(path, "file contains no faces"));
+    }
+
+    Ok(TriangleMesh::with_attributes(
+        positions,
+        (!normals.is_empty()).then_some(normals),
+        (!uvs.is_empty()).then_some(uvs),
+        (!colors.is_empty()).then_some(colors),
+        indices,
+        material,
+    ))
+}
+
+type Header = (Format, Vec<Element>, usize, usize);
+
+fn parse_header(path: &Path, data: &[u8]) -> Result<Header, LoadError> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut offset = 0;
+    let mut line_number = 0;
+
+    loop {
+        let end = data[offset..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| LoadError::invalid(path, "header is missing end_header"))?;
+        let line = String::from_utf8_lossy(&data[offset..offset + end]);
+        let line = line.trim();
+        offset += end + 1;
+        line_number += 1;
+
+        let error = |message: String| LoadError::parse(path, line_number, message);
+        let mut tokens = line.split_whitespace();
+
+        if line_number == 1 {
+            if line != "ply" {
+                return Err(error("not a PLY file".into()));
+            }
+            continue;
+        }
+
+        match tokens.next() {
+            Some("format") => {
+                format = Some(match tokens.next() {
+                    Some("ascii") => Format::Ascii,
+                    Some("binary_little_endian") => Format::BinaryLittleEndian,
+                    Some("binary_big_endian") => Format::BinaryBigEndian,
+                    other => return Err(error(format!("unknown format '{}'", other.unwrap_or("")))),
+                });
+            }
+            Some("element") => {
+                let name = tokens.next().ok_or_else(|| error("element without a name".into()))?;
+                let count = tokens
+                    .next()
+                    .and_then(|count| count.parse().ok())
+                    .ok_or_else(|| error(format!("element '{name}' has no valid count")))?;
+                elements.push(Element {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            Some("property") => {
+                let element = elements.last_mut().ok_or_else(|| error("property before any element".into()))?;
+                let scalar = |name: Option<&str>| {
+                    name.and_then(Scalar::parse)
+                        .ok_or_else(|| error(format!("unknown property type '{}'", name.unwrap_or(""))))
+                };
+                let kind = match tokens.next() {
+                    Some("list") => PropertyKind::List {
+                        count: scalar(tokens.next())?,
+                        item: scalar(tokens.next())?,
+                    },
+                    name => PropertyKind::Scalar(scalar(name)?),
+                };
+                let name = tokens.next().ok_or_else(|| error("property without a name".into()))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    kind,
+                });
+            }
+            Some("end_header") => break,
+            Some("comment" | "obj_info") | None => {}
+            Some(keyword) => return Err(error(format!("unexpected header keyword '{keyword}'"))),
+        }
+    }
+
+    let format = format.ok_or_else(|| LoadError::invalid(path, "header has no format line"))?;
+    Ok((format, elements, line_number, offset))
+}
+
+fn read_vertices(
+    element: &Element,
+    body: &mut Body,
+    positions: &mut Vec<Point3>,
+    normals: &mut Vec<Vec3>,
+    uvs: &mut Vec<(f64, f64)>,
+    colors: &mut Vec<Color>,
+) -> Result<(), String> {
+    let find = |names: &[&str]| element.properties.iter().position(|p| names.contains(&p.name.as_str()));
+    let position = [find(&["x"]), find(&["y"]), find(&["z"])];
+    let normal = [find(&["nx"]), find(&["ny"]), find(&["nz"])];
+    let uv = [find(&["u", "s", "texture_u", "texture_s"]), find(&["v", "t", "texture_v", "texture_t"])];
+    let color = [find(&["red", "r"]), find(&["green", "g"]), find(&["blue", "b"])];
+
+    let [Some(x), Some(y), Some(z)] = position else {
+        return Err("vertex element needs x, y and z properties".into());
+    };
+    let color_scale = |i: usize| match element.properties[i].kind {
+        PropertyKind::Scalar(scalar) => scalar.color_scale(),
+        PropertyKind::List { .. } => 0.0,
+    };
+    // Integer channels store display-encoded values, floats are already linear.
+    let is_float = |i: usize| matches!(element.properties[i].kind, PropertyKind::Scalar(Scalar::F32 | Scalar::F64));
+
+    let mut values = vec![0.0; element.properties.len()];
+    for _ in 0..element.count {
+        for (value, property) in values.iter_mut().zip(&element.properties) {
+            *value = match property.kind {
+                PropertyKind::Scalar(scalar) => body.read(scalar)?,
+                PropertyKind::List { count, item } => {
+                    for _ in 0..body.read(count)? as usize {
+                        body.read(item)?;
+                    }
+                    0.0
+                }
+            };
+        }
+
+        positions.push(Point3::from(values[x], values[y], values[z]));
+        if let [Some(nx), Some(ny), Some(nz)] = normal {
+            normals.push(Vec3::from(values[nx], values[ny], values[nz]));
+        }
+        if let [Some(u), Some(v)] = uv {
+            uvs.push((values[u], values[v]));
+        }
+        if let [Some(r), Some(g), Some(b)] = color {
+            let channel = |i: usize| {
+                let value = values[i] * color_scale(i);
+                if is_float(i) { value } else { gamma_to_linear(value) }
+            };
+            colors.push(Color::from(channel(r), channel(g), channel(b)));
+        }
+    }
+
+    Ok(())
+}
+
+fn read_faces(element: &Element, body: &mut Body, indices: &mut Vec<[u32; 3]>) -> Result<(), String> {
+    let list = element
+        .properties
+        .iter()
+        .position(|p| p.name == "vertex_indices" || p.name == "vertex_index")
+        .ok_or("face element needs a vertex_indices list")?;
+
+    let mut polygon = Vec::new();
+    for _ in 0..element.count {
+        for (i, property) in element.properties.iter().enumerate() {
+            match property.kind {
+                PropertyKind::Scalar(scalar) => {
+                    body.read(scalar)?;
+                }
+                PropertyKind::List { count, item } => {
+                    let count = body.read(count)? as usize;
+                    if i != list {
+                        for _ in 0..count {
+                            body.read(item)?;
+                        }
+                        continue;
+                    }
+                    polygon.clear();
+                    for _ in 0..count {
+                        let index = body.read(item)?;
+                        if index < 0.0 {
+                            return Err(format!("negative vertex index {index}"));
+                        }
+                        polygon.push(index as u32);
+                    }
+                    if polygon.len() < 3 {
+                        return Err(format!("face has {} vertices, expected at least 3", polygon.len()));
+                    }
+                    for j in 1..polygon.len() - 1 {
+                        indices.push([polygon[0], polygon[j], polygon[j + 1]]);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn skip_element(element: &Element, body: &mut Body) -> Result<(), String> {
+    for _ in 0..element.count {
+        for property in &element.properties {
+            match property.kind {
+                PropertyKind::Scalar(scalar) => {
+                    body.read(scalar)?;
+                }
+                PropertyKind::List { count, item } => {
+                    for _ in 0..body.read(count)? as usize {
+                        body.read(item)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::Hittable;
+    use crate::loader::write_temp;
+    use crate::material::Lambertian;
+    use crate::ray::Ray;
+    use crate::utils::{fPI, Interval};
+
+    const SQUARE: [[f32; 3]; 4] = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+
+    fn load(name: &str, contents: &[u8]) -> Result<Arc<TriangleMesh>, LoadError> {
+        load_ply(write_temp(name, contents), Lambertian::from((0.5, 0.5, 0.5)))
+    }
+
+    /// A square as one quad face, followed by a face property list holding
+    /// negative values that must not be read as indices.
+    fn binary_square(big_endian: bool) -> Vec<u8> {
+        let format = if big_endian { "binary_big_endian" } else { "binary_little_endian" };
+        let mut data = format!(
+            "ply\nformat {format} 1.0\nelement vertex 4\nproperty float x\nproperty float y\nproperty float z\n\
+             element face 1\nproperty list uchar int vertex_indices\nproperty list uchar float weights\nend_header\n"
+        )
+        .into_bytes();
+        let word = |bytes: [u8; 4]| if big_endian { u32::from_le_bytes(bytes).to_be_bytes() } else { bytes };
+
+        for coordinate in SQUARE.into_iter().flatten() {
+            data.extend(word(coordinate.to_le_bytes()));
+        }
+        data.push(4);
+        for index in [0i32, 1, 2, 3] {
+            data.extend(word(index.to_le_bytes()));
+        }
+        data.push(2);
+        for weight in [-1.0f32, -2.0] {
+            data.extend(word(weight.to_le_bytes()));
+        }
+        data
+    }
+
+    #[test]
+    fn reads_ascii_polygons_as_fans() {
+        let mesh = load(
+            "square.ply",
+            b"ply\nformat ascii 1.0\ncomment unit square\nelement vertex 4\nproperty float x\nproperty float y\nproperty float z\n\
+              property uchar red\nproperty uchar green\nproperty uchar blue\n\
+              element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+              0 0 0 255 0 0\n1 0 0 0 255 0\n1 1 0 0 0 255\n0 1 0 255 255 255\n4 0 1 2 3\n",
+        )
+        .unwrap();
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.triangle_count(), 2);
+    }
+
+    /// Albedo seen by a white Lambertian mesh loaded from a triangle whose
+    /// vertices all carry `color`, written as properties of type `scalar`.
+    fn albedo_of_uniform_triangle(scalar: &str, color: &str) -> Color {
+        let source = format!(
+            "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+             property {scalar} red\nproperty {scalar} green\nproperty {scalar} blue\n\
+             element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+             0 0 0 {color}\n1 0 0 {color}\n0 1 0 {color}\n3 0 1 2\n"
+        );
+        let path = write_temp(&format!("colored-{scalar}.ply"), source.as_bytes());
+        let mesh = load_ply(path, Lambertian::from((1.0, 1.0, 1.0))).unwrap();
+
+        let ray = Ray::from((0.25, 0.25, 1.0), (0.0, 0.0, -1.0));
+        let rec = mesh.hit(ray, Interval::from(0.001, f64::INFINITY)).expect("ray should hit the triangle");
+        // A Lambertian BSDF along the normal is albedo / pi.
+        rec.material.eval(ray, &rec, rec.normal) * fPI
+    }
+
+    #[test]
+    fn vertex_colors_reach_the_material_albedo() {
+        let decoded = gamma_to_linear(128.0 / 255.0);
+        let albedo = albedo_of_uniform_triangle("uchar", "255 128 0");
+        assert!((albedo - Color::from(1.0, decoded, 0.0)).length() < 1e-12);
+
+        let albedo = albedo_of_uniform_triangle("float", "1 0.5 0");
+        assert!((albedo - Color::from(1.0, 0.5, 0.0)).length() < 1e-12);
+    }
+
+    #[test]
+    fn drops_degenerate_faces() {
+        let header = "ply\nformat ascii 1.0\nelement vertex 4\nproperty float x\nproperty float y\nproperty float z\n\
+                      element face 2\nproperty list uchar int vertex_indices\nend_header\n";
+        let mesh = load("collinear.ply", format!("{header}0 0 0\n1 0 0\n2 0 0\n0 1 0\n3 0 1 2\n3 0 1 3\n").as_bytes()).unwrap();
+        assert_eq!(mesh.triangle_count(), 1);
+
+        assert!(matches!(
+            load("flat.ply", format!("{header}0 0 0\n1 0 0\n2 0 0\n0 1 0\n3 0 1 2\n3 3 3 3\n").as_bytes()),
+            Err(LoadError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn reads_both_binary_byte_orders() {
+        for big_endian in [false, true] {
+            let mesh = load(&format!("square-{big_endian}.ply"), &binary_square(big_endian)).unwrap();
+            assert_eq!(mesh.vertex_count(), 4);
+            assert_eq!(mesh.triangle_count(), 2);
+        }
+    }
+
+    #[test]
+    fn color_scales_reach_one_at_the_type_maximum() {
+        assert_eq!(Scalar::U8.color_scale() * u8::MAX as f64, 1.0);
+        assert_eq!(Scalar::I8.color_scale() * i8::MAX as f64, 1.0);
+        assert_eq!(Scalar::I16.color_scale() * i16::MAX as f64, 1.0);
+        assert_eq!(Scalar::I32.color_scale() * i32::MAX as f64, 1.0);
+        assert_eq!(Scalar::U32.color_scale() * u32::MAX as f64, 1.0);
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        let header = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+                      element face 1\nproperty list uchar int vertex_indices\nend_header\n";
+
+        assert!(matches!(load("magic.ply", b"plx\nformat ascii 1.0\nend_header\n"), Err(LoadError::Parse { line: 1, .. })));
+        assert!(matches!(load("unterminated.ply", b"ply\nformat ascii 1.0\n"), Err(LoadError::Invalid { .. })));
+        assert!(matches!(
+            load("number.ply", format!("{header}0 0 0\n1 x 0\n0 1 0\n3 0 1 2\n").as_bytes()),
+            Err(LoadError::Parse { line: 11, .. })
+        ));
+        assert!(matches!(
+            load("negative.ply", format!("{header}0 0 0\n1 0 0\n0 1 0\n3 0 -1 2\n").as_bytes()),
+            Err(LoadError::Parse { .. })
+        ));
+        assert!(matches!(
+            load("range.ply", format!("{header}0 0 0\n1 0 0\n0 1 0\n3 0 1 7\n").as_bytes()),
+            Err(LoadError::Invalid { .. })
+        ));
+
+        let mut truncated = binary_square(false);
+        truncated.truncate(truncated.len() - 6);
+        assert!(matches!(load("truncated.ply", &truncated), Err(LoadError::Invalid { .. })));
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use crate::hittable::TriangleMesh;
+use crate::loader::{is_degenerate, parse_next, LoadError};
+use crate::material::Material;
+use crate::utils::Point3;
+
+const BINARY_HEADER_SIZE: usize = 84;
+const BINARY_TRIANGLE_SIZE: usize = 50;
+
+/// Merges STL's unshared facet corners into an indexed vertex buffer.
+#[derive(Default)]
+struct VertexBuffer {
+    positions: Vec<Point3>,
+    lookup: HashMap<[u64; 3], u32>,
+}
+
+impl VertexBuffer {
+    fn index(&mut self, p: Point3) -> u32 {
+        let key = [p.x.to_bits(), p.y.to_bits(), p.z.to_bits()];
+        *self.lookup.entry(key).or_insert_with(|| {
+            self.positions.push(p);
+            (self.positions.len() - 1) as u32
+        })
+    }
+}
+
+/// Loads an ASCII or binary STL file as an indexed mesh, merging identical
+/// corners. STL facet normals are ignored in favour of the winding order.
+pub fn load_stl(path: impl AsRef<Path>, material: Arc<dyn Material>) -> Result<Arc<TriangleMesh>, LoadError> {
+    let path = path.as_ref();
+    let data = fs::read(path).map_err(|e| LoadError::io(path, e))?;
+
+    let triangles = if is_binary(&data) {
+        read_binary(&data)
+    } else if data.trim_ascii_start().starts_with(b"solid") {
+        let text = std::str::from_utf8(&data).map_err(|_| LoadError::invalid(path, "ASCII STL is not valid UTF-8"))?;
+        read_ascii(path, text)?
+    } else {
+        return Err(LoadError::invalid(path, "neither a binary nor an ASCII STL file"));
+    };
+
+    let mut vertices = VertexBuffer::default();
+    let indices: Vec<[u32; 3]> = triangles
+        .into_iter()
+        .filter(|&triangle| !is_degenerate(triangle))
+        .map(|triangle| triangle.map(|p| vertices.index(p)))
+        .collect();
+    if indices.is_empty() {
+        return Err(LoadError::invalid(path, "file contains no triangles"));
+    }
+
+    Ok(TriangleMesh::from(vertices.positions, indices, material))
+}
+
+/// Binary files may also begin with "solid", so the size implied by the
+/// triangle count is the reliable test.
+fn is_binary(data: &[u8]) -> bool {
+    if data.len() < BINARY_HEADER_SIZE {
+        return false;
+    }
+    let count = u32::from_le_bytes([data[80], data[81], data[82], data[83]]) as usize;
+    data.len() == BINARY_HEADER_SIZE + count * BINARY_TRIANGLE_SIZE
+}
+
+fn read_binary(data: &[u8]) -> Vec<[Point3; 3]> {
+    data[BINARY_HEADER_SIZE..]
+        .chunks_exact(BINARY_TRIANGLE_SIZE)
+        .map(|chunk| {
+            let float = |i: usize| f32::from_le_bytes([chunk[i], chunk[i + 1], chunk[i + 2], chunk[i + 3]]) as f64;
+            let point = |i: usize| Point3::from(float(i), float(i + 4), float(i + 8));
+            [point(12), point(24), point(36)]
+        })
+        .collect()
+}
+
+fn read_ascii(path: &Path, text: &str) -> Result<Vec<[Point3; 3]>, LoadError> {
+    let mut triangles = Vec::new();
+    let mut corners: Vec<Point3> = Vec::with_capacity(3);
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let mut tokens = line.split_whitespace();
+        let error = |message: String| LoadError::parse(path, line_number, message);
+
+        match tokens.next() {
+            Some("vertex") => {
+                if corners.len() == 3 {
+                    return Err(error("facet has more than three vertices".into()));
+                }
+                corners.push(Point3::from(
+                    parse_next(&mut tokens, "vertex x").map_err(error)?,
+                    parse_next(&mut tokens, "vertex y").map_err(error)?,
+                    parse_next(&mut tokens, "vertex z").map_err(error)?,
+                ));
+            }
+            Some("endfacet") => {
+                let [a, b, c] = corners[..] else {
+                    return Err(error(format!("facet has {} vertices, expected 3", corners.len())));
+                };
+                triangles.push([a, b, c]);
+                corners.clear();
+            }
+            Some("solid" | "facet" | "outer" | "endloop" | "endsolid") | None => {}
+            Some(keyword) => return Err(error(format!("unexpected keyword '{keyword}'"))),
+        }
+    }
+
+    Ok(triangles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::write_temp;
+    use crate::material::Lambertian;
+
+    fn load(name: &str, contents: &[u8]) -> Result<Arc<TriangleMesh>, LoadError> {
+        load_stl(write_temp(name, contents), Lambertian::from((0.5, 0.5, 0.5)))
+    }
+
+    /// Two triangles sharing an edge, with a header that starts like ASCII.
+    fn binary_square() -> Vec<u8> {
+        let mut data = b"solid but actually binary".to_vec();
+        data.resize(80, 0);
+        data.extend(2u32.to_le_bytes());
+        let triangles = [[[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]], [[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]];
+        for triangle in triangles {
+            data.extend([0u8; 12]);
+            for coordinate in triangle.into_iter().flatten() {
+                data.extend(coordinate.to_le_bytes());
+            }
+            data.extend([0u8; 2]);
+        }
+        data
+    }
+
+    #[test]
+    fn reads_ascii_and_merges_corners() {
+        let mesh = load(
+            "square.stl",
+            b"solid square\n\
+              facet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 1 1 0\nendloop\nendfacet\n\
+              facet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 1 0\nvertex 0 1 0\nendloop\nendfacet\n\
+              endsolid square\n",
+        )
+        .unwrap();
+        assert_eq!(mesh.triangle_count(), 2);
+        assert_eq!(mesh.vertex_count(), 4);
+    }
+
+    #[test]
+    fn reads_binary_despite_solid_header() {
+        let mesh = load("square-binary.stl", &binary_square()).unwrap();
+        assert_eq!(mesh.triangle_count(), 2);
+        assert_eq!(mesh.vertex_count(), 4);
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        assert!(matches!(
+            load("short.stl", b"solid s\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nendloop\nendfacet\n"),
+            Err(LoadError::Parse { line: 7, .. })
+        ));
+        assert!(matches!(load("keyword.stl", b"solid s\nfacet normal 0 0 1\nbogus\n"), Err(LoadError::Parse { line: 3, .. })));
+        assert!(matches!(load("garbage.stl", b"not a mesh"), Err(LoadError::Invalid { .. })));
+        assert!(matches!(
+            load("degenerate.stl", b"solid s\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 2 0 0\nendloop\nendfacet\n"),
+            Err(LoadError::Invalid { .. })
+        ));
+
+        let mut truncated = binary_square();
+        truncated.pop();
+        assert!(load("truncated.stl", &truncated).is_err());
+    }
+}
@@ -1,20 +1,11 @@
-mod camera;
-mod color;
-mod hittable;
-mod image;
-mod ray;
-mod utils;
-mod material;
-
-use std::rc::Rc;
-use hittable::HittableList;
-use hittable::Sphere;
-use utils::Point3;
-use camera::Camera;
-use image::Image;
-use crate::color::Color;
-use crate::material::{Dielectric, Lambertian, Material, Metal};
-use crate::utils::{fPI, rand_f64, rand_f64_in, Vec3};
+use crayfish::hittable::{BvhNode, HittableList, SahConfig};
+use crayfish::hittable::Sphere;
+use crayfish::utils::Point3;
+use crayfish::camera::Camera;
+use crayfish::image::Image;
+use crayfish::color::Color;
+use crayfish::material::{Dielectric, Lambertian, Metal};
+use crayfish::utils::{rand_f64, rand_f64_in, Vec3};
 
 fn main() {
     let mut image = Image::from(
@@ -57,7 +48,10 @@ fn main() {
         }
     }
 
-    camera.render(&mut image, &world);
+    let (world, stats) = BvhNode::with_sah(world, SahConfig::default());
+    println!("BVH: {stats}");
+
+    camera.render(&mut image, world.as_ref());
 
     image.save("image.png");
 }
@@ -1,14 +1,147 @@
 use crate::color::Color;
 use crate::hittable::HitRecord;
 use crate::ray::Ray;
+use crate::utils::{Point3, Vec3};
 mod lambertian;
 mod metal;
 mod dielectric;
+mod diffuse_light;
 
-pub trait Material {
-    fn scatter(&self, ray: Ray, rec: HitRecord) -> Option<(Ray, Color)>;
+/// A sampled bounce. `bsdf` is the BSDF times the cosine at the sampled
+/// direction and `pdf` its density over solid angle, so a path's throughput
+/// is scaled by `bsdf / pdf`. Specular (delta) lobes have no density; they
+/// carry their reflectance in `bsdf` with `pdf` 1.
+#[derive(Copy, Clone)]
+pub struct ScatterRecord {
+    pub ray: Ray,
+    pub bsdf: Color,
+    pub pdf: f64,
+    pub is_specular: bool,
+}
+
+impl ScatterRecord {
+    pub fn from(ray: Ray, bsdf: impl Into<Color>, pdf: f64) -> Self {
+        Self {
+            ray,
+            bsdf: bsdf.into(),
+            pdf,
+            is_specular: false,
+        }
+    }
+
+    pub fn specular(ray: Ray, reflectance: impl Into<Color>) -> Self {
+        Self {
+            ray,
+            bsdf: reflectance.into(),
+            pdf: 1.0,
+            is_specular: true,
+        }
+    }
+
+    /// Factor the incoming radiance is multiplied by.
+    pub fn weight(&self) -> Color {
+        if self.pdf > 0.0 {
+            self.bsdf / self.pdf
+        } else {
+            Color::zeros()
+        }
+    }
+}
+
+pub trait Material: Send + Sync {
+    fn scatter(&self, ray: Ray, rec: &HitRecord) -> Option<ScatterRecord>;
+
+    /// BSDF times cosine for light leaving along `-ray.direction` that
+    /// arrived from `direction`. Zero for specular lobes, which only
+    /// `scatter` can sample.
+    fn eval(&self, _ray: Ray, _rec: &HitRecord, _direction: Vec3) -> Color {
+        Color::zeros()
+    }
+
+    /// Density over solid angle with which `scatter` picks `direction`.
+    fn pdf(&self, _ray: Ray, _rec: &HitRecord, _direction: Vec3) -> f64 {
+        0.0
+    }
+
+    /// Radiance given off at a surface point. Most materials emit nothing.
+    fn emitted(&self, _u: f64, _v: f64, _p: Point3) -> Color {
+        Color::zeros()
+    }
 }
 
 pub use lambertian::Lambertian;
 pub use metal::Metal;
-pub use dielectric::Dielectric;
\ No newline at end of file
+pub use dielectric::Dielectric;
+pub use diffuse_light::DiffuseLight;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fPI;
+    use std::sync::Arc;
+
+    /// A hit at the origin on the z = 0 plane, facing +z.
+    fn hit(material: Arc<dyn Material>, direction: Vec3) -> (Ray, HitRecord) {
+        let ray = Ray::from(-direction, direction);
+        let rec = HitRecord::from((0.0, 0.0, 0.0), 1.0, (0.5, 0.5), ray, material, (0.0, 0.0, 1.0));
+        (ray, rec)
+    }
+
+    /// Whether sampled directions carry the same BSDF and density that
+    /// `eval` and `pdf` report for them.
+    fn scatter_agrees_with_eval_and_pdf(material: Arc<dyn Material>, direction: Vec3) -> bool {
+        let (ray, rec) = hit(material.clone(), direction);
+        (0..1000).filter_map(|_| material.scatter(ray, &rec)).all(|scatter| {
+            let direction = scatter.ray.direction;
+            let pdf = material.pdf(ray, &rec, direction);
+            let tolerance = 1e-9 * pdf.max(1.0);
+            !scatter.is_specular
+                && (scatter.pdf - pdf).abs() <= tolerance
+                && (scatter.bsdf - material.eval(ray, &rec, direction)).length() <= tolerance
+        })
+    }
+
+    /// Monte Carlo estimate of the integral of `material.pdf` over all
+    /// directions, from uniform sphere samples.
+    fn pdf_integral(material: Arc<dyn Material>, direction: Vec3) -> f64 {
+        let (ray, rec) = hit(material.clone(), direction);
+        let samples = 400_000;
+        let sum: f64 = (0..samples).map(|_| material.pdf(ray, &rec, Vec3::random_unit_vector())).sum();
+        4.0 * fPI * sum / samples as f64
+    }
+
+    #[test]
+    fn lambertian_pdf_agrees_with_scatter() {
+        let material = Lambertian::from((0.8, 0.5, 0.2));
+        let direction = Vec3::from(1.0, 0.0, -1.0);
+        assert!(scatter_agrees_with_eval_and_pdf(material.clone(), direction));
+        assert!((pdf_integral(material, direction) - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn fuzzy_metal_pdf_agrees_with_scatter() {
+        let material = Metal::from((0.9, 0.9, 0.9), 0.7);
+        // Arriving at a grazing angle so part of the lobe falls below the surface.
+        let direction = Vec3::from(1.0, 0.0, -0.3);
+        assert!(scatter_agrees_with_eval_and_pdf(material.clone(), direction));
+
+        // Samples pushed below the surface are absorbed, so the density
+        // covers only the accepted fraction of the lobe.
+        let (ray, rec) = hit(material.clone(), direction);
+        let accepted = (0..200_000).filter(|_| material.scatter(ray, &rec).is_some()).count() as f64 / 200_000.0;
+        assert!(accepted < 0.9);
+        assert!((pdf_integral(material, direction) - accepted).abs() < 0.03);
+    }
+
+    #[test]
+    fn mirrors_and_glass_are_specular() {
+        let mirror = Metal::from((0.9, 0.9, 0.9), 0.0);
+        let glass = Dielectric::from(1.5);
+        for material in [mirror as Arc<dyn Material>, glass] {
+            let (ray, rec) = hit(material.clone(), Vec3::from(1.0, 0.0, -1.0));
+            let scatter = material.scatter(ray, &rec).expect("specular materials always scatter");
+            assert!(scatter.is_specular);
+            assert_eq!(material.pdf(ray, &rec, scatter.ray.direction), 0.0);
+        }
+    }
+}
@@ -1,7 +1,7 @@
-use std::rc::Rc;
+use std::sync::Arc;
 use crate::color::Color;
 use crate::hittable::HitRecord;
-use crate::material::Material;
+use crate::material::{Material, ScatterRecord};
 use crate::ray::Ray;
 use crate::utils::{rand_f64, Vec3};
 
@@ -15,8 +15,8 @@ impl Dielectric {
         Self::default()
     }
 
-    pub fn from(refraction_index: f64) -> Rc<Self> {
-        Rc::new(Self {
+    pub fn from(refraction_index: f64) -> Arc<Self> {
+        Arc::new(Self {
             refraction_index
         })
     }
@@ -29,7 +29,7 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray: Ray, rec: HitRecord) -> Option<(Ray, Color)> {
+    fn scatter(&self, ray: Ray, rec: &HitRecord) -> Option<ScatterRecord> {
         let ri = if rec.front_face {1.0/self.refraction_index} else {self.refraction_index};
         let unit_direction = Vec3::unit_vector(ray.direction);
         let cos_theta = Vec3::dot(-unit_direction, rec.normal).min(1.0);
@@ -42,6 +42,6 @@ impl Material for Dielectric {
             Vec3::refract(unit_direction, rec.normal, ri)
         };
 
-        Some((Ray::with_time(rec.p, direction, ray.time), Color::ones()))
+        Some(ScatterRecord::specular(Ray::with_time(rec.p, direction, ray.time), Color::ones()))
     }
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::material::{Material, ScatterRecord};
+use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
+use crate::utils::Point3;
+
+/// Emits the same radiance in every direction from both sides, and scatters
+/// nothing. Colors brighter than one make useful light sources.
+#[derive(Clone)]
+pub struct DiffuseLight {
+    texture: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(emit: impl Into<Color>) -> Arc<Self> {
+        Self::with_texture(SolidColor::from(emit))
+    }
+
+    pub fn with_texture(texture: Arc<dyn Texture>) -> Arc<Self> {
+        Arc::new(Self { texture })
+    }
+}
+
+impl Default for DiffuseLight {
+    fn default() -> Self {
+        Self {
+            texture: SolidColor::from(Color::ones()),
+        }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray: Ray, _rec: &HitRecord) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: Point3) -> Color {
+        self.texture.value(u, v, p)
+    }
+}
@@ -1,13 +1,14 @@
-use std::rc::Rc;
+use std::sync::Arc;
 use crate::color::Color;
 use crate::hittable::HitRecord;
-use crate::material::Material;
+use crate::material::{Material, ScatterRecord};
 use crate::ray::Ray;
-use crate::utils::Vec3;
+use crate::texture::{SolidColor, Texture};
+use crate::utils::{fPI, Vec3};
 
-#[derive(Default, Copy, Clone)]
+#[derive(Clone)]
 pub struct Lambertian {
-    albedo: Color,
+    texture: Arc<dyn Texture>,
 }
 
 impl Lambertian {
@@ -15,21 +16,52 @@ impl Lambertian {
         Self::default()
     }
     
-    pub fn from(albedo: impl Into<Color>) -> Rc<Self> {
-        Rc::new(Self {
-            albedo: albedo.into(),
-        })
+    pub fn from(albedo: impl Into<Color>) -> Arc<Self> {
+        Self::with_texture(SolidColor::from(albedo))
+    }
+
+    pub fn with_texture(texture: Arc<dyn Texture>) -> Arc<Self> {
+        Arc::new(Self { texture })
+    }
+
+    /// Texture color, tinted by the vertex color when the mesh has one.
+    fn albedo(&self, rec: &HitRecord) -> Color {
+        let texel = self.texture.sample(rec);
+        rec.vertex_color.map_or(texel, |color| color * texel)
+    }
+}
+
+impl Default for Lambertian {
+    fn default() -> Self {
+        Self {
+            texture: Arc::new(SolidColor::new()),
+        }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, ray: Ray, rec: HitRecord) -> Option<(Ray, Color)> {
+    /// Cosine-weighted hemisphere sampling, so the weight is just the albedo.
+    fn scatter(&self, ray: Ray, rec: &HitRecord) -> Option<ScatterRecord> {
         let mut scatter_direction = rec.normal + Vec3::random_unit_vector();
         
         if scatter_direction.near_zero() {
             scatter_direction = rec.normal;
         }
-        
-        Some((Ray::with_time(rec.p, scatter_direction, ray.time), self.albedo))
+
+        let scattered = Ray::with_time(rec.p, scatter_direction, ray.time);
+        let cosine = Vec3::dot(Vec3::unit_vector(scatter_direction), rec.normal).max(0.0);
+        Some(ScatterRecord::from(scattered, self.albedo(rec) * cosine / fPI, cosine / fPI))
+    }
+
+    fn eval(&self, _ray: Ray, rec: &HitRecord, direction: Vec3) -> Color {
+        let cosine = Vec3::dot(Vec3::unit_vector(direction), rec.normal);
+        if cosine <= 0.0 {
+            return Color::zeros();
+        }
+        self.albedo(rec) * cosine / fPI
+    }
+
+    fn pdf(&self, _ray: Ray, rec: &HitRecord, direction: Vec3) -> f64 {
+        Vec3::dot(Vec3::unit_vector(direction), rec.normal).max(0.0) / fPI
     }
-}
\ No newline at end of file
+}
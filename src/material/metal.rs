@@ -1,13 +1,14 @@
-use std::rc::Rc;
+use std::sync::Arc;
 use crate::color::Color;
 use crate::hittable::HitRecord;
-use crate::material::Material;
+use crate::material::{Material, ScatterRecord};
 use crate::ray::Ray;
-use crate::utils::Vec3;
+use crate::texture::{SolidColor, Texture};
+use crate::utils::{fPI, Vec3};
 
-#[derive(Default, Copy, Clone)]
+#[derive(Clone)]
 pub struct Metal {
-    albedo: Color,
+    texture: Arc<dyn Texture>,
     fuzz: f64,
 }
 
@@ -16,22 +17,75 @@ impl Metal {
         Self::default()
     }
 
-    pub fn from(albedo: impl Into<Color>, fuzz: f64) -> Rc<Self> {
-        Rc::new(Self {
-            albedo: albedo.into(),
-            fuzz: fuzz.min(1.0),
+    pub fn from(albedo: impl Into<Color>, fuzz: f64) -> Arc<Self> {
+        Self::with_texture(SolidColor::from(albedo), fuzz)
+    }
+
+    pub fn with_texture(texture: Arc<dyn Texture>, fuzz: f64) -> Arc<Self> {
+        Arc::new(Self {
+            texture,
+            fuzz: fuzz.clamp(0.0, 1.0),
         })
     }
+
+    /// Density over solid angle of `mirror + fuzz * s` pointing along
+    /// `direction`, for `s` uniform on the unit sphere. Each point where the
+    /// line along `direction` crosses the fuzz sphere contributes
+    /// t^2 / (4 pi fuzz sqrt(disc)).
+    fn lobe_pdf(&self, mirror: Vec3, direction: Vec3) -> f64 {
+        let direction = Vec3::unit_vector(direction);
+        let c = Vec3::dot(direction, mirror);
+        let discriminant = c * c - 1.0 + self.fuzz * self.fuzz;
+        if discriminant <= 0.0 {
+            return 0.0;
+        }
+        let root = discriminant.sqrt();
+        [c - root, c + root]
+            .iter()
+            .filter(|&&t| t > 0.0)
+            .map(|t| t * t / (4.0 * fPI * self.fuzz * root))
+            .sum()
+    }
+}
+
+impl Default for Metal {
+    fn default() -> Self {
+        Self {
+            texture: Arc::new(SolidColor::new()),
+            fuzz: 0.0,
+        }
+    }
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray: Ray, rec: HitRecord) -> Option<(Ray, Color)> {
-        let reflected = Vec3::unit_vector(Vec3::reflect(ray.direction, rec.normal)) + (self.fuzz * Vec3::random_unit_vector());
-        let scattered = Ray::with_time(rec.p, reflected, ray.time);
-        if Vec3::dot(scattered.direction, rec.normal) > 0.0 {
-            Some((scattered, self.albedo))
-        } else {
-            None
+    /// The mirror direction jittered by a point on a sphere of radius `fuzz`;
+    /// rays pushed below the surface are absorbed. Without fuzz this is a
+    /// perfect mirror.
+    fn scatter(&self, ray: Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let mirror = Vec3::unit_vector(Vec3::reflect(ray.direction, rec.normal));
+        let albedo = self.texture.sample(rec);
+        if self.fuzz <= 0.0 {
+            return Some(ScatterRecord::specular(Ray::with_time(rec.p, mirror, ray.time), albedo));
         }
+
+        let reflected = mirror + (self.fuzz * Vec3::random_unit_vector());
+        if Vec3::dot(reflected, rec.normal) <= 0.0 {
+            return None;
+        }
+        let pdf = self.lobe_pdf(mirror, reflected);
+        Some(ScatterRecord::from(Ray::with_time(rec.p, reflected, ray.time), albedo * pdf, pdf))
+    }
+
+    /// The fuzzed lobe has no closed-form BSDF; defining it as albedo times
+    /// the sampling density keeps `eval / pdf` equal to the albedo.
+    fn eval(&self, ray: Ray, rec: &HitRecord, direction: Vec3) -> Color {
+        self.texture.sample(rec) * self.pdf(ray, rec, direction)
     }
-}
\ No newline at end of file
+
+    fn pdf(&self, ray: Ray, rec: &HitRecord, direction: Vec3) -> f64 {
+        if self.fuzz <= 0.0 || Vec3::dot(direction, rec.normal) <= 0.0 {
+            return 0.0;
+        }
+        self.lobe_pdf(Vec3::unit_vector(Vec3::reflect(ray.direction, rec.normal)), direction)
+    }
+}
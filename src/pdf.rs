@@ -0,0 +1,173 @@
+use crate::background::Background;
+use crate::hittable::Hittable;
+use crate::utils::{rand_f64, Point3, Vec3};
+
+/// A distribution of directions that can be sampled and evaluated, with
+/// densities over solid angle.
+pub trait Pdf {
+    fn value(&self, direction: Vec3) -> f64;
+
+    fn generate(&self) -> Vec3;
+}
+
+/// Directions from `origin` towards points on `objects`.
+#[derive(Copy, Clone)]
+pub struct HittablePdf<'a> {
+    objects: &'a dyn Hittable,
+    origin: Point3,
+    time: f64,
+}
+
+impl<'a> HittablePdf<'a> {
+    pub fn from(objects: &'a dyn Hittable, origin: impl Into<Point3>, time: f64) -> Self {
+        Self {
+            objects,
+            origin: origin.into(),
+            time,
+        }
+    }
+}
+
+impl Pdf for HittablePdf<'_> {
+    fn value(&self, direction: Vec3) -> f64 {
+        self.objects.pdf_value(self.origin, direction, self.time)
+    }
+
+    fn generate(&self) -> Vec3 {
+        self.objects.random(self.origin, self.time)
+    }
+}
+
+/// Directions towards the background, as the background samples them.
+#[derive(Copy, Clone)]
+pub struct BackgroundPdf<'a> {
+    background: &'a dyn Background,
+}
+
+impl<'a> BackgroundPdf<'a> {
+    pub fn from(background: &'a dyn Background) -> Self {
+        Self { background }
+    }
+}
+
+impl Pdf for BackgroundPdf<'_> {
+    fn value(&self, direction: Vec3) -> f64 {
+        self.background.pdf(direction)
+    }
+
+    fn generate(&self) -> Vec3 {
+        self.background.sample().0
+    }
+}
+
+/// Samples `first` with probability `weight` and `second` otherwise.
+#[derive(Copy, Clone)]
+pub struct MixturePdf<A, B> {
+    first: A,
+    second: B,
+    weight: f64,
+}
+
+impl<A: Pdf, B: Pdf> MixturePdf<A, B> {
+    pub fn from(first: A, second: B, weight: f64) -> Self {
+        Self {
+            first,
+            second,
+            weight: weight.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl<A: Pdf, B: Pdf> Pdf for MixturePdf<A, B> {
+    fn value(&self, direction: Vec3) -> f64 {
+        self.weight * self.first.value(direction) + (1.0 - self.weight) * self.second.value(direction)
+    }
+
+    fn generate(&self) -> Vec3 {
+        if rand_f64() < self.weight {
+            self.first.generate()
+        } else {
+            self.second.generate()
+        }
+    }
+}
+
+/// What direct lighting samples at a bounce: the lights, an
+/// importance-sampled background, or a mixture of both. An enum rather than a
+/// boxed `Pdf` so building one per bounce never allocates.
+#[derive(Copy, Clone)]
+pub enum LightPdf<'a> {
+    Lights(HittablePdf<'a>),
+    Background(BackgroundPdf<'a>),
+    Both(MixturePdf<HittablePdf<'a>, BackgroundPdf<'a>>),
+}
+
+impl Pdf for LightPdf<'_> {
+    fn value(&self, direction: Vec3) -> f64 {
+        match self {
+            Self::Lights(pdf) => pdf.value(direction),
+            Self::Background(pdf) => pdf.value(direction),
+            Self::Both(pdf) => pdf.value(direction),
+        }
+    }
+
+    fn generate(&self) -> Vec3 {
+        match self {
+            Self::Lights(pdf) => pdf.generate(),
+            Self::Background(pdf) => pdf.generate(),
+            Self::Both(pdf) => pdf.generate(),
+        }
+    }
+}
+
+/// How multiple importance sampling splits a contribution between two
+/// strategies that could both have produced it.
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MisHeuristic {
+    /// Weights proportional to each strategy's density.
+    Balance,
+    /// Weights proportional to the squared densities, which favours the
+    /// clearly better strategy more strongly.
+    #[default]
+    Power,
+}
+
+impl MisHeuristic {
+    /// Weight for a sample taken with density `pdf` when the other strategy
+    /// would have produced it with density `other_pdf`.
+    pub fn weight(self, pdf: f64, other_pdf: f64) -> f64 {
+        let (a, b) = match self {
+            Self::Balance => (pdf, other_pdf),
+            Self::Power => (pdf * pdf, other_pdf * other_pdf),
+        };
+        if a + b > 0.0 { a / (a + b) } else { 0.0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weights_of_both_strategies_sum_to_one() {
+        let pdfs = [0.0, 1e-6, 0.1, 0.5, 1.0, 7.5, 1e4];
+        for heuristic in [MisHeuristic::Balance, MisHeuristic::Power] {
+            for a in pdfs {
+                for b in pdfs {
+                    if a + b == 0.0 {
+                        continue;
+                    }
+                    let total = heuristic.weight(a, b) + heuristic.weight(b, a);
+                    assert!((total - 1.0).abs() < 1e-12, "{heuristic:?} {a} {b}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn power_heuristic_favours_the_denser_strategy_more() {
+        assert_eq!(MisHeuristic::Balance.weight(3.0, 1.0), 0.75);
+        assert_eq!(MisHeuristic::Power.weight(3.0, 1.0), 0.9);
+        assert_eq!(MisHeuristic::Power.weight(0.0, 0.0), 0.0);
+    }
+}
@@ -5,6 +5,9 @@ pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
     pub time: f64,
+    /// Angle in radians the ray's cone widens by per unit of distance along
+    /// `direction`, used to pick texture detail. Zero is an infinitely thin ray.
+    pub spread: f64,
 }
 
 impl Ray {
@@ -17,6 +20,7 @@ impl Ray {
             origin:origin.into(),
             direction:direction.into(),
             time: 0.0,
+            spread: 0.0,
         }
     }
 
@@ -27,6 +31,18 @@ impl Ray {
         }
     }
 
+    pub fn with_spread(self, spread: f64) -> Self {
+        Self {
+            spread,
+            ..self
+        }
+    }
+
+    /// Width of the ray's cone at parameter `t`.
+    pub fn width_at(&self, t: f64) -> f64 {
+        self.spread * t * self.direction.length()
+    }
+
     pub fn at(&self, t: f64) -> Point3 {
         self.origin + t * self.direction
     }
@@ -0,0 +1,26 @@
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::utils::Point3;
+
+mod solid_color;
+mod checker;
+mod image_texture;
+mod mipmap;
+mod perlin;
+mod noise;
+
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f64, v: f64, p: Point3) -> Color;
+
+    /// Value at a hit, which filtered textures override to account for the
+    /// ray's footprint.
+    fn sample(&self, rec: &HitRecord) -> Color {
+        self.value(rec.u, rec.v, rec.p)
+    }
+}
+
+pub use solid_color::SolidColor;
+pub use checker::Checker;
+pub use image_texture::{AddressMode, ColorSpace, Filter, ImageTexture, Sampler};
+pub use perlin::Perlin;
+pub use noise::{NoisePattern, NoiseTexture};
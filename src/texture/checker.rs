@@ -0,0 +1,50 @@
+use std::sync::Arc;
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::texture::{SolidColor, Texture};
+use crate::utils::Point3;
+
+/// Solid 3D checkerboard alternating between two textures in cubes of side
+/// `scale`.
+#[derive(Clone)]
+pub struct Checker {
+    inverse_scale: f64,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl Checker {
+    pub fn from(scale: f64, even: impl Into<Color>, odd: impl Into<Color>) -> Arc<Self> {
+        Self::with_textures(scale, SolidColor::from(even), SolidColor::from(odd))
+    }
+
+    pub fn with_textures(scale: f64, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Arc<Self> {
+        Arc::new(Self {
+            inverse_scale: 1.0 / scale,
+            even,
+            odd,
+        })
+    }
+
+    fn texture_at(&self, p: Point3) -> &dyn Texture {
+        let x = (self.inverse_scale * p.x).floor() as i64;
+        let y = (self.inverse_scale * p.y).floor() as i64;
+        let z = (self.inverse_scale * p.z).floor() as i64;
+
+        if (x + y + z).rem_euclid(2) == 0 {
+            self.even.as_ref()
+        } else {
+            self.odd.as_ref()
+        }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f64, v: f64, p: Point3) -> Color {
+        self.texture_at(p).value(u, v, p)
+    }
+
+    fn sample(&self, rec: &HitRecord) -> Color {
+        self.texture_at(rec.p).sample(rec)
+    }
+}
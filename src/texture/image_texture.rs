@@ -0,0 +1,180 @@
+use std::path::Path;
+use std::sync::Arc;
+use image::ImageResult;
+use crate::color::{gamma_to_linear, Color};
+use crate::hittable::HitRecord;
+use crate::texture::mipmap::{MipLevel, MipMap};
+use crate::texture::Texture;
+use crate::utils::Point3;
+
+/// What happens to texture coordinates outside [0, 1].
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AddressMode {
+    /// Tile the image.
+    #[default]
+    Wrap,
+    /// Repeat the edge texels.
+    Clamp,
+    /// Tile the image, flipping every other copy.
+    Mirror,
+}
+
+impl AddressMode {
+    fn apply(self, i: i64, size: u32) -> u32 {
+        let size = size as i64;
+        let i = match self {
+            Self::Wrap => i.rem_euclid(size),
+            Self::Clamp => i.clamp(0, size - 1),
+            Self::Mirror => {
+                let i = i.rem_euclid(2 * size);
+                if i < size { i } else { 2 * size - 1 - i }
+            }
+        };
+        i as u32
+    }
+}
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Filter {
+    /// The single closest texel.
+    Nearest,
+    /// Blend of the four closest texels of the full-size image.
+    Bilinear,
+    /// Bilinear lookups in the two mipmap levels nearest the ray's footprint,
+    /// blended together.
+    #[default]
+    Trilinear,
+}
+
+/// How stored texel values relate to linear light.
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ColorSpace {
+    /// Gamma encoded, as written by `write_color`; decoded on load.
+    #[default]
+    Srgb,
+    /// Already linear, such as normal or roughness maps.
+    Linear,
+}
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Sampler {
+    pub address: AddressMode,
+    pub filter: Filter,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(address: AddressMode, filter: Filter) -> Self {
+        Self { address, filter }
+    }
+}
+
+/// Texture looked up from an image by (u, v), with v = 0 at the bottom row.
+#[derive(Clone)]
+pub struct ImageTexture {
+    mipmap: MipMap,
+    sampler: Sampler,
+}
+
+impl ImageTexture {
+    /// Loads a gamma-encoded image with wrapping, trilinear filtering.
+    pub fn load(path: impl AsRef<Path>) -> ImageResult<Arc<Self>> {
+        Self::load_with(path, ColorSpace::Srgb, Sampler::default())
+    }
+
+    pub fn load_with(path: impl AsRef<Path>, color_space: ColorSpace, sampler: Sampler) -> ImageResult<Arc<Self>> {
+        let image = image::open(path)?.into_rgb8();
+        let (width, height) = image.dimensions();
+        let decode = |c: u8| match color_space {
+            ColorSpace::Srgb => gamma_to_linear(c as f64 / 255.0),
+            ColorSpace::Linear => c as f64 / 255.0,
+        };
+        let texels = image
+            .pixels()
+            .map(|pixel| Color::from(decode(pixel[0]), decode(pixel[1]), decode(pixel[2])))
+            .collect();
+        Ok(Self::from_texels(width, height, texels, sampler))
+    }
+
+    /// A texture over linear `texels`, stored row by row from the top.
+    pub fn from_texels(width: u32, height: u32, texels: Vec<Color>, sampler: Sampler) -> Arc<Self> {
+        assert!(width > 0 && height > 0, "texture must have at least one texel");
+        let base = MipLevel::from(width, height, texels);
+        Arc::new(Self {
+            mipmap: MipMap::from(base, sampler.filter == Filter::Trilinear),
+            sampler,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.mipmap.base().width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.mipmap.base().height
+    }
+
+    pub fn sampler(&self) -> Sampler {
+        self.sampler
+    }
+
+    /// Looks up (u, v) for a footprint `width` UV units across.
+    pub fn lookup(&self, u: f64, v: f64, width: f64) -> Color {
+        let v = 1.0 - v;
+        match self.sampler.filter {
+            Filter::Nearest => self.nearest(self.mipmap.base(), u, v),
+            Filter::Bilinear => self.bilinear(self.mipmap.base(), u, v),
+            Filter::Trilinear => {
+                let texels = width * self.width().max(self.height()) as f64;
+                let lod = texels.max(1.0).log2().min((self.mipmap.level_count() - 1) as f64);
+                let level = lod.floor() as usize;
+                let blend = lod - level as f64;
+
+                let fine = self.bilinear(self.mipmap.level(level), u, v);
+                if blend <= 0.0 {
+                    return fine;
+                }
+                let coarse = self.bilinear(self.mipmap.level(level + 1), u, v);
+                (1.0 - blend) * fine + blend * coarse
+            }
+        }
+    }
+
+    fn nearest(&self, level: &MipLevel, u: f64, v: f64) -> Color {
+        let address = self.sampler.address;
+        let x = address.apply((u * level.width as f64).floor() as i64, level.width);
+        let y = address.apply((v * level.height as f64).floor() as i64, level.height);
+        level.texel(x, y)
+    }
+
+    /// Texel centres sit at half-integer coordinates, so shift by half a
+    /// texel before splitting into the four neighbours and their weights.
+    fn bilinear(&self, level: &MipLevel, u: f64, v: f64) -> Color {
+        let address = self.sampler.address;
+        let x = u * level.width as f64 - 0.5;
+        let y = v * level.height as f64 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let [x0, x1] = [x0, x0 + 1].map(|x| address.apply(x, level.width));
+        let [y0, y1] = [y0, y0 + 1].map(|y| address.apply(y, level.height));
+
+        let top = (1.0 - fx) * level.texel(x0, y0) + fx * level.texel(x1, y0);
+        let bottom = (1.0 - fx) * level.texel(x0, y1) + fx * level.texel(x1, y1);
+        (1.0 - fy) * top + fy * bottom
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _p: Point3) -> Color {
+        self.lookup(u, v, 0.0)
+    }
+
+    fn sample(&self, rec: &HitRecord) -> Color {
+        self.lookup(rec.u, rec.v, rec.footprint)
+    }
+}
@@ -0,0 +1,70 @@
+use crate::color::Color;
+
+/// One level of a mipmap pyramid, stored row by row from the top.
+#[derive(Clone)]
+pub(crate) struct MipLevel {
+    pub width: u32,
+    pub height: u32,
+    texels: Vec<Color>,
+}
+
+impl MipLevel {
+    pub fn from(width: u32, height: u32, texels: Vec<Color>) -> Self {
+        assert_eq!(texels.len(), width as usize * height as usize, "texel count must match the image size");
+        Self { width, height, texels }
+    }
+
+    pub fn texel(&self, x: u32, y: u32) -> Color {
+        self.texels[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Box-filters 2x2 blocks into a level of half the size, rounding down
+    /// but never below one texel.
+    fn downsample(&self) -> Self {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut texels = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..height {
+            let (y0, y1) = ((2 * y).min(self.height - 1), (2 * y + 1).min(self.height - 1));
+            for x in 0..width {
+                let (x0, x1) = ((2 * x).min(self.width - 1), (2 * x + 1).min(self.width - 1));
+                let sum = self.texel(x0, y0) + self.texel(x1, y0) + self.texel(x0, y1) + self.texel(x1, y1);
+                texels.push(sum / 4.0);
+            }
+        }
+        Self { width, height, texels }
+    }
+}
+
+/// An image and its successively halved copies down to a single texel.
+#[derive(Clone)]
+pub(crate) struct MipMap {
+    levels: Vec<MipLevel>,
+}
+
+impl MipMap {
+    /// Builds the full pyramid when `full` is set, otherwise keeps only the
+    /// base level.
+    pub fn from(base: MipLevel, full: bool) -> Self {
+        let mut levels = vec![base];
+        if full {
+            while let Some(last) = levels.last().filter(|level| level.width > 1 || level.height > 1) {
+                let next = last.downsample();
+                levels.push(next);
+            }
+        }
+        Self { levels }
+    }
+
+    pub fn base(&self) -> &MipLevel {
+        &self.levels[0]
+    }
+
+    pub fn level(&self, index: usize) -> &MipLevel {
+        &self.levels[index.min(self.levels.len() - 1)]
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+use crate::color::Color;
+use crate::texture::{Perlin, Texture};
+use crate::utils::{Point3, Vec3};
+
+const OCTAVES: u32 = 7;
+
+#[derive(Default, Copy, Clone, PartialEq, Debug)]
+pub enum NoisePattern {
+    /// Plain Perlin noise.
+    #[default]
+    Perlin,
+    /// Sum of noise magnitudes, with creases where the noise crosses zero.
+    Turbulence,
+    /// Fractional Brownian motion with the given lacunarity and gain.
+    Fbm { lacunarity: f64, gain: f64 },
+    /// Sine bands along z, bent by turbulence.
+    Marble,
+    /// Rings around the y axis, wobbled by turbulence.
+    Wood,
+}
+
+/// Procedural texture blending from `low` to `high` by a noise pattern
+/// evaluated at the hit point, with features about `1 / scale` across.
+#[derive(Clone)]
+pub struct NoiseTexture {
+    perlin: Perlin,
+    pattern: NoisePattern,
+    scale: f64,
+    low: Color,
+    high: Color,
+}
+
+impl NoiseTexture {
+    pub fn from(pattern: NoisePattern, scale: f64, seed: u64) -> Arc<Self> {
+        Self::with_colors(pattern, scale, seed, Color::zeros(), Color::ones())
+    }
+
+    pub fn with_colors(pattern: NoisePattern, scale: f64, seed: u64, low: impl Into<Color>, high: impl Into<Color>) -> Arc<Self> {
+        Arc::new(Self {
+            perlin: Perlin::from(seed),
+            pattern,
+            scale,
+            low: low.into(),
+            high: high.into(),
+        })
+    }
+
+    /// Blend factor in [0, 1] at `p`.
+    pub fn intensity(&self, point: Point3) -> f64 {
+        let p = self.scale * point;
+        let intensity = match self.pattern {
+            NoisePattern::Perlin => 0.5 * (1.0 + self.perlin.noise(p)),
+            NoisePattern::Turbulence => self.perlin.turbulence(p, OCTAVES),
+            NoisePattern::Fbm { lacunarity, gain } => 0.5 * (1.0 + self.perlin.fbm(p, OCTAVES, lacunarity, gain)),
+            // Turbulence at the unscaled point keeps the veins broad while
+            // `scale` sets how many bands there are.
+            NoisePattern::Marble => 0.5 * (1.0 + (p.z + 10.0 * self.perlin.turbulence(point, OCTAVES)).sin()),
+            NoisePattern::Wood => {
+                let radius = Vec3::from(p.x, 0.0, p.z).length() + 0.5 * self.perlin.turbulence(p, OCTAVES);
+                radius - radius.floor()
+            }
+        };
+        intensity.clamp(0.0, 1.0)
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, p: Point3) -> Color {
+        let t = self.intensity(p);
+        (1.0 - t) * self.low + t * self.high
+    }
+}
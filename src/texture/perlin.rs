@@ -0,0 +1,143 @@
+use rand::Rng;
+use crate::utils::{Point3, Vec3};
+
+const POINT_COUNT: usize = 256;
+
+/// SplitMix64, kept local so seeded tables never change with the `rand` version.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [-1, 1).
+    fn next_signed(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    }
+
+    /// Uniform in [0, n) for small n.
+    fn next_below(&mut self, n: usize) -> usize {
+        (((self.next_u64() >> 32) * n as u64) >> 32) as usize
+    }
+}
+
+/// Gradient noise over a lattice of random unit vectors. The same seed always
+/// builds the same tables, so renders using it are reproducible.
+#[derive(Clone)]
+pub struct Perlin {
+    gradients: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    /// Noise with a randomly chosen seed.
+    pub fn new() -> Self {
+        Self::from(rand::rng().random())
+    }
+
+    pub fn from(seed: u64) -> Self {
+        let mut rng = SplitMix64(seed);
+        let gradients = (0..POINT_COUNT)
+            .map(|_| loop {
+                let p = Vec3::from(
+                    rng.next_signed(),
+                    rng.next_signed(),
+                    rng.next_signed(),
+                );
+                let length_squared = p.length_squared();
+                if length_squared > 1e-6 && length_squared <= 1.0 {
+                    break Vec3::unit_vector(p);
+                }
+            })
+            .collect();
+        let mut permutation = || {
+            let mut perm: Vec<usize> = (0..POINT_COUNT).collect();
+            for i in (1..perm.len()).rev() {
+                perm.swap(i, rng.next_below(i + 1));
+            }
+            perm
+        };
+        let perm_x = permutation();
+        let perm_y = permutation();
+        let perm_z = permutation();
+
+        Self {
+            gradients,
+            perm_x,
+            perm_y,
+            perm_z,
+        }
+    }
+
+    /// Smoothly varying noise in roughly [-1, 1], zero at lattice points.
+    pub fn noise(&self, p: Point3) -> f64 {
+        let (i, j, k) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (u, v, w) = (p.x - i, p.y - j, p.z - k);
+        let (i, j, k) = (i as i64, j as i64, k as i64);
+
+        // Hermite smoothing hides the lattice in the interpolated result.
+        let (uu, vv, ww) = (u * u * (3.0 - 2.0 * u), v * v * (3.0 - 2.0 * v), w * w * (3.0 - 2.0 * w));
+        let mask = POINT_COUNT as i64 - 1;
+        let mut sum = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let index = self.perm_x[((i + di) & mask) as usize]
+                        ^ self.perm_y[((j + dj) & mask) as usize]
+                        ^ self.perm_z[((k + dk) & mask) as usize];
+                    let (fi, fj, fk) = (di as f64, dj as f64, dk as f64);
+                    let weight = Vec3::from(u - fi, v - fj, w - fk);
+                    sum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * Vec3::dot(self.gradients[index], weight);
+                }
+            }
+        }
+        sum
+    }
+
+    /// Fractal sum of noise magnitudes, doubling frequency and halving
+    /// amplitude each octave. Always non-negative.
+    pub fn turbulence(&self, p: Point3, octaves: u32) -> f64 {
+        let mut sum = 0.0;
+        let mut point = p;
+        let mut weight = 1.0;
+        for _ in 0..octaves {
+            sum += weight * self.noise(point).abs();
+            weight *= 0.5;
+            point = 2.0 * point;
+        }
+        sum
+    }
+
+    /// Fractional Brownian motion: signed noise summed over octaves that step
+    /// frequency by `lacunarity` and amplitude by `gain`, normalised back to
+    /// roughly [-1, 1].
+    pub fn fbm(&self, p: Point3, octaves: u32, lacunarity: f64, gain: f64) -> f64 {
+        let mut sum = 0.0;
+        let mut total_weight = 0.0;
+        let mut point = p;
+        let mut weight = 1.0;
+        for _ in 0..octaves {
+            sum += weight * self.noise(point);
+            total_weight += weight;
+            weight *= gain;
+            point = lacunarity * point;
+        }
+        if total_weight > 0.0 { sum / total_weight } else { 0.0 }
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
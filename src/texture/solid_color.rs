@@ -0,0 +1,27 @@
+use std::sync::Arc;
+use crate::color::Color;
+use crate::texture::Texture;
+use crate::utils::Point3;
+
+#[derive(Default, Copy, Clone)]
+pub struct SolidColor {
+    albedo: Color,
+}
+
+impl SolidColor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(albedo: impl Into<Color>) -> Arc<Self> {
+        Arc::new(Self {
+            albedo: albedo.into(),
+        })
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f64, _v: f64, _p: Point3) -> Color {
+        self.albedo
+    }
+}
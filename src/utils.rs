@@ -3,6 +3,10 @@ use rand::Rng;
 
 mod vec3;
 mod interval;
+mod aabb;
+mod matrix;
+mod quaternion;
+mod distribution;
 
 #[inline(always)]
 pub fn degrees_to_radians(degrees: f64) -> f64 {
@@ -25,4 +29,8 @@ pub fn rand_f64_in(min: f64, max: f64) -> f64 {
 }
 
 pub use vec3::{Vec3, Point3};
-pub use interval::Interval;
\ No newline at end of file
+pub use interval::Interval;
+pub use aabb::Aabb;
+pub use matrix::Mat4;
+pub use quaternion::Quat;
+pub use distribution::{Distribution1D, Distribution2D};
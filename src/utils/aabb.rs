@@ -0,0 +1,129 @@
+use crate::ray::Ray;
+use crate::utils::{Interval, Point3};
+
+#[derive(Default, Copy, Clone)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Aabb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(x: impl Into<Interval>, y: impl Into<Interval>, z: impl Into<Interval>) -> Self {
+        Self {
+            x: x.into(),
+            y: y.into(),
+            z: z.into(),
+        }
+    }
+
+    pub fn from_points(a: impl Into<Point3>, b: impl Into<Point3>) -> Self {
+        let a = a.into();
+        let b = b.into();
+        Self {
+            x: Interval::from(a.x.min(b.x), a.x.max(b.x)),
+            y: Interval::from(a.y.min(b.y), a.y.max(b.y)),
+            z: Interval::from(a.z.min(b.z), a.z.max(b.z)),
+        }
+    }
+
+    pub fn from_boxes(a: Aabb, b: Aabb) -> Self {
+        Self {
+            x: Interval::from_intervals(a.x, b.x),
+            y: Interval::from_intervals(a.y, b.y),
+            z: Interval::from_intervals(a.z, b.z),
+        }
+    }
+
+    /// Widens any side thinner than `delta` so flat primitives still have a
+    /// box the slab test can hit.
+    pub fn pad_to_minimums(&self, delta: f64) -> Self {
+        let pad = |interval: Interval| if interval.size() < delta { interval.expand(delta) } else { interval };
+        Self {
+            x: pad(self.x),
+            y: pad(self.y),
+            z: pad(self.z),
+        }
+    }
+
+    pub fn axis_interval(&self, axis: usize) -> Interval {
+        match axis {
+            1 => self.y,
+            2 => self.z,
+            _ => self.x,
+        }
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        if self.x.size() > self.y.size() {
+            if self.x.size() > self.z.size() { 0 } else { 2 }
+        } else if self.y.size() > self.z.size() {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        Point3::from(
+            0.5 * (self.x.min + self.x.max),
+            0.5 * (self.y.min + self.y.max),
+            0.5 * (self.z.min + self.z.max),
+        )
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let x = self.x.size().max(0.0);
+        let y = self.y.size().max(0.0);
+        let z = self.z.size().max(0.0);
+        2.0 * (x * y + y * z + z * x)
+    }
+
+    pub fn hit(&self, ray: Ray, t: Interval) -> bool {
+        let origin = [ray.origin.x, ray.origin.y, ray.origin.z];
+        let direction = [ray.direction.x, ray.direction.y, ray.direction.z];
+        let mut t = t;
+
+        for axis in 0..3 {
+            let bounds = self.axis_interval(axis);
+            let inverse = 1.0 / direction[axis];
+
+            let t0 = (bounds.min - origin[axis]) * inverse;
+            let t1 = (bounds.max - origin[axis]) * inverse;
+
+            if t0 < t1 {
+                t.min = t.min.max(t0);
+                t.max = t.max.min(t1);
+            } else {
+                t.min = t.min.max(t1);
+                t.max = t.max.min(t0);
+            }
+
+            if t.max <= t.min {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub const fn empty() -> Self {
+        Self {
+            x: Interval::empty(),
+            y: Interval::empty(),
+            z: Interval::empty(),
+        }
+    }
+
+    pub const fn universe() -> Self {
+        Self {
+            x: Interval::universe(),
+            y: Interval::universe(),
+            z: Interval::universe(),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+/// Piecewise-constant density over [0, 1) with one step per entry of `func`,
+/// sampled by inverting its CDF.
+#[derive(Clone, Default)]
+pub struct Distribution1D {
+    func: Vec<f64>,
+    cdf: Vec<f64>,
+    integral: f64,
+}
+
+impl Distribution1D {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Negative values count as zero. An all-zero function falls back to a
+    /// uniform density so sampling never fails.
+    pub fn from(func: Vec<f64>) -> Self {
+        assert!(!func.is_empty(), "distribution needs at least one value");
+        let n = func.len() as f64;
+        let func: Vec<f64> = func.into_iter().map(|f| if f > 0.0 { f } else { 0.0 }).collect();
+
+        let mut cdf = Vec::with_capacity(func.len() + 1);
+        cdf.push(0.0);
+        for f in &func {
+            cdf.push(cdf[cdf.len() - 1] + f / n);
+        }
+        let integral = cdf[cdf.len() - 1];
+
+        if integral > 0.0 {
+            cdf.iter_mut().for_each(|c| *c /= integral);
+        } else {
+            cdf.iter_mut().enumerate().for_each(|(i, c)| *c = i as f64 / n);
+        }
+        let last = cdf.len() - 1;
+        cdf[last] = 1.0;
+
+        Self { func, cdf, integral }
+    }
+
+    pub fn len(&self) -> usize {
+        self.func.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.func.is_empty()
+    }
+
+    /// Integral of the function over [0, 1).
+    pub fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    /// Maps a uniform `u` in [0, 1) to a point in [0, 1), returning it with its
+    /// density and the index of the step it fell in.
+    pub fn sample(&self, u: f64) -> (f64, f64, usize) {
+        let index = (self.cdf.partition_point(|&c| c <= u) - 1).min(self.len() - 1);
+        let width = self.cdf[index + 1] - self.cdf[index];
+        let offset = if width > 0.0 { (u - self.cdf[index]) / width } else { 0.0 };
+        let x = ((index as f64 + offset) / self.len() as f64).min(1.0 - f64::EPSILON);
+        (x, self.step_pdf(index), index)
+    }
+
+    /// Density at `x` in [0, 1).
+    pub fn pdf(&self, x: f64) -> f64 {
+        self.step_pdf(self.index_of(x))
+    }
+
+    fn index_of(&self, x: f64) -> usize {
+        ((x * self.len() as f64) as usize).min(self.len() - 1)
+    }
+
+    fn step_pdf(&self, index: usize) -> f64 {
+        if self.integral > 0.0 {
+            self.func[index] / self.integral
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Piecewise-constant density over the unit square, stored as `height` rows
+/// of `width` values, sampled by choosing a row from the marginal
+/// distribution and then a column within it.
+#[derive(Clone, Default)]
+pub struct Distribution2D {
+    conditionals: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from(func: &[f64], width: usize, height: usize) -> Self {
+        assert_eq!(func.len(), width * height, "distribution needs width * height values");
+        let conditionals: Vec<Distribution1D> = func.chunks_exact(width).map(|row| Distribution1D::from(row.to_vec())).collect();
+        let marginal = Distribution1D::from(conditionals.iter().map(Distribution1D::integral).collect());
+        Self { conditionals, marginal }
+    }
+
+    /// Maps two uniform numbers to a point (x, y) in the unit square, with y
+    /// indexing rows, and returns it with its density.
+    pub fn sample(&self, u1: f64, u2: f64) -> ((f64, f64), f64) {
+        let (y, marginal_pdf, row) = self.marginal.sample(u2);
+        let (x, conditional_pdf, _) = self.conditionals[row].sample(u1);
+        ((x, y), marginal_pdf * conditional_pdf)
+    }
+
+    /// Density at (x, y) in the unit square.
+    pub fn pdf(&self, x: f64, y: f64) -> f64 {
+        let row = self.marginal.index_of(y);
+        self.marginal.pdf(y) * self.conditionals[row].pdf(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUNC: [f64; 5] = [1.0, 0.0, 3.0, 0.5, 2.5];
+
+    #[test]
+    fn density_integrates_to_one() {
+        let distribution = Distribution1D::from(FUNC.to_vec());
+        let n = FUNC.len() as f64;
+        let total: f64 = (0..FUNC.len()).map(|i| distribution.pdf((i as f64 + 0.5) / n) / n).sum();
+        assert!((total - 1.0).abs() < 1e-12);
+        assert!((distribution.integral() - 7.0 / n).abs() < 1e-12);
+    }
+
+    #[test]
+    fn samples_agree_with_pdf_and_follow_the_function() {
+        let distribution = Distribution1D::from(FUNC.to_vec());
+        let samples = 10_000;
+        let mut counts = [0usize; 5];
+        for i in 0..samples {
+            let (x, pdf, index) = distribution.sample((i as f64 + 0.5) / samples as f64);
+            assert!((0.0..1.0).contains(&x));
+            assert_eq!(index, distribution.index_of(x));
+            assert!((pdf - distribution.pdf(x)).abs() < 1e-12);
+            counts[index] += 1;
+        }
+        for (count, f) in counts.iter().zip(FUNC) {
+            let expected = f / 7.0 * samples as f64;
+            assert!((*count as f64 - expected).abs() <= 1.0, "{count} vs {expected}");
+        }
+    }
+
+    #[test]
+    fn all_zero_function_is_uniform() {
+        let distribution = Distribution1D::from(vec![0.0, -1.0, 0.0]);
+        assert_eq!(distribution.pdf(0.5), 1.0);
+        let (x, pdf, _) = distribution.sample(0.7);
+        assert!((x - 0.7).abs() < 1e-12 && pdf == 1.0);
+    }
+
+    #[test]
+    fn two_dimensional_density_integrates_to_one_and_matches_samples() {
+        let (width, height) = (4, 3);
+        let func: Vec<f64> = (0..width * height).map(|i| ((i * 7) % 5) as f64).collect();
+        let distribution = Distribution2D::from(&func, width, height);
+
+        let mut total = 0.0;
+        for y in 0..height {
+            for x in 0..width {
+                let (u, v) = ((x as f64 + 0.5) / width as f64, (y as f64 + 0.5) / height as f64);
+                total += distribution.pdf(u, v) / (width * height) as f64;
+            }
+        }
+        assert!((total - 1.0).abs() < 1e-12);
+
+        for i in 0..50 {
+            for j in 0..50 {
+                let ((x, y), pdf) = distribution.sample((i as f64 + 0.5) / 50.0, (j as f64 + 0.5) / 50.0);
+                assert!(pdf > 0.0);
+                assert!((pdf - distribution.pdf(x, y)).abs() < 1e-9);
+            }
+        }
+    }
+}
@@ -17,6 +17,13 @@ impl Interval {
         }
     }
 
+    pub fn from_intervals(a: Interval, b: Interval) -> Self {
+        Self {
+            min: a.min.min(b.min),
+            max: a.max.max(b.max),
+        }
+    }
+
     pub fn size(&self) -> f64 {
         self.max - self.min
     }
@@ -33,6 +40,14 @@ impl Interval {
         x.max(self.min).min(self.max)
     }
 
+    pub fn expand(&self, delta: f64) -> Self {
+        let padding = delta / 2.0;
+        Self {
+            min: self.min - padding,
+            max: self.max + padding,
+        }
+    }
+
     pub const fn empty() -> Self {
         Self {
             min: f64::INFINITY,
@@ -0,0 +1,245 @@
+use std::ops::Mul;
+use crate::utils::{degrees_to_radians, Point3, Vec3};
+
+/// Row-major 4x4 matrix for affine transforms of points, vectors and normals.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Mat4 {
+    pub m: [[f64; 4]; 4],
+}
+
+impl Mat4 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub const fn from(m: [[f64; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub const fn identity() -> Self {
+        Self {
+            m: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    pub fn translation(offset: impl Into<Vec3>) -> Self {
+        let offset = offset.into();
+        Self {
+            m: [
+                [1.0, 0.0, 0.0, offset.x],
+                [0.0, 1.0, 0.0, offset.y],
+                [0.0, 0.0, 1.0, offset.z],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    pub fn scaling(factors: impl Into<Vec3>) -> Self {
+        let factors = factors.into();
+        Self {
+            m: [
+                [factors.x, 0.0, 0.0, 0.0],
+                [0.0, factors.y, 0.0, 0.0],
+                [0.0, 0.0, factors.z, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    /// Counter-clockwise rotation by `degrees` about `axis` (right-handed).
+    pub fn rotation(axis: impl Into<Vec3>, degrees: f64) -> Self {
+        let a = Vec3::unit_vector(axis.into());
+        let (sin, cos) = degrees_to_radians(degrees).sin_cos();
+        let k = 1.0 - cos;
+        Self {
+            m: [
+                [cos + a.x * a.x * k, a.x * a.y * k - a.z * sin, a.x * a.z * k + a.y * sin, 0.0],
+                [a.y * a.x * k + a.z * sin, cos + a.y * a.y * k, a.y * a.z * k - a.x * sin, 0.0],
+                [a.z * a.x * k - a.y * sin, a.z * a.y * k + a.x * sin, cos + a.z * a.z * k, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    pub fn rotation_x(degrees: f64) -> Self {
+        Self::rotation(Vec3::unit_x(), degrees)
+    }
+
+    pub fn rotation_y(degrees: f64) -> Self {
+        Self::rotation(Vec3::unit_y(), degrees)
+    }
+
+    pub fn rotation_z(degrees: f64) -> Self {
+        Self::rotation(Vec3::unit_z(), degrees)
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Self { m }
+    }
+
+    /// Determinant of the upper-left 3x3 block, which for an affine matrix is
+    /// the determinant of the whole.
+    pub fn determinant(&self) -> f64 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]) - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// Gauss-Jordan inverse with partial pivoting, or `None` when singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inverse = Self::identity().m;
+
+        for column in 0..4 {
+            let pivot = (column..4).max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))?;
+            if a[pivot][column].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(column, pivot);
+            inverse.swap(column, pivot);
+
+            let scale = 1.0 / a[column][column];
+            for j in 0..4 {
+                a[column][j] *= scale;
+                inverse[column][j] *= scale;
+            }
+
+            for row in 0..4 {
+                if row == column {
+                    continue;
+                }
+                let factor = a[row][column];
+                for j in 0..4 {
+                    a[row][j] -= factor * a[column][j];
+                    inverse[row][j] -= factor * inverse[column][j];
+                }
+            }
+        }
+
+        Some(Self { m: inverse })
+    }
+
+    #[inline(always)]
+    pub fn transform_point(&self, p: Point3) -> Point3 {
+        let m = &self.m;
+        let x = m[0][0] * p.x + m[0][1] * p.y + m[0][2] * p.z + m[0][3];
+        let y = m[1][0] * p.x + m[1][1] * p.y + m[1][2] * p.z + m[1][3];
+        let z = m[2][0] * p.x + m[2][1] * p.y + m[2][2] * p.z + m[2][3];
+        let w = m[3][0] * p.x + m[3][1] * p.y + m[3][2] * p.z + m[3][3];
+        if w == 1.0 {
+            Point3::from(x, y, z)
+        } else {
+            Point3::from(x / w, y / w, z / w)
+        }
+    }
+
+    #[inline(always)]
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::from(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+
+    /// Transforms a normal by this matrix's inverse transpose; pass the inverse
+    /// of the transform being applied to the surface.
+    #[inline(always)]
+    pub fn transform_normal(&self, n: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::from(
+            m[0][0] * n.x + m[1][0] * n.y + m[2][0] * n.z,
+            m[0][1] * n.x + m[1][1] * n.y + m[2][1] * n.z,
+            m[0][2] * n.x + m[1][2] * n.y + m[2][2] * n.z,
+        )
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul<Mat4> for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, rhs: Mat4) -> Self::Output {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Self::Output { m }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Mat4, b: Mat4) {
+        for (row_a, row_b) in a.m.iter().zip(&b.m) {
+            for (x, y) in row_a.iter().zip(row_b) {
+                assert!((x - y).abs() < 1e-9, "{a:?} != {b:?}");
+            }
+        }
+    }
+
+    fn affine() -> Mat4 {
+        Mat4::translation((1.0, -2.0, 3.0)) * Mat4::rotation((1.0, 1.0, 0.0), 40.0) * Mat4::scaling((2.0, 0.5, 3.0))
+    }
+
+    #[test]
+    fn inverse_undoes_the_transform() {
+        let m = affine();
+        let inverse = m.inverse().expect("affine transform is invertible");
+        assert_close(m * inverse, Mat4::identity());
+        assert_close(inverse * m, Mat4::identity());
+
+        let p = Point3::from(0.3, -0.7, 1.1);
+        assert!((inverse.transform_point(m.transform_point(p)) - p).length() < 1e-9);
+    }
+
+    #[test]
+    fn singular_matrix_has_no_inverse() {
+        assert!(Mat4::scaling((1.0, 0.0, 1.0)).inverse().is_none());
+    }
+
+    #[test]
+    fn determinant_is_the_volume_scale() {
+        assert!((affine().determinant() - 3.0).abs() < 1e-9);
+        assert!((Mat4::scaling((-1.0, 1.0, 1.0)).determinant() + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn normals_stay_perpendicular_to_transformed_surfaces() {
+        let m = affine();
+        let inverse = m.inverse().unwrap();
+        let (tangent, bitangent) = (Vec3::from(1.0, 2.0, 0.0), Vec3::from(0.0, 1.0, -1.0));
+        let normal = Vec3::cross(tangent, bitangent);
+
+        let n = inverse.transform_normal(normal);
+        assert!(Vec3::dot(n, m.transform_vector(tangent)).abs() < 1e-9);
+        assert!(Vec3::dot(n, m.transform_vector(bitangent)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn vectors_ignore_translation() {
+        let v = Vec3::from(1.0, 2.0, 3.0);
+        assert!((Mat4::translation((5.0, 5.0, 5.0)).transform_vector(v) - v).near_zero());
+    }
+}
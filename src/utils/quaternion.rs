@@ -0,0 +1,161 @@
+use std::ops::Mul;
+use crate::utils::{degrees_to_radians, Mat4, Vec3};
+
+/// Unit quaternion representing a rotation.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Quat {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Quat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub const fn from(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Self { w, x, y, z }
+    }
+
+    pub const fn identity() -> Self {
+        Self {
+            w: 1.0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    /// Counter-clockwise rotation by `degrees` about `axis`, matching
+    /// `Mat4::rotation`.
+    pub fn from_axis_angle(axis: impl Into<Vec3>, degrees: f64) -> Self {
+        let axis = Vec3::unit_vector(axis.into());
+        let (sin, cos) = (degrees_to_radians(degrees) / 2.0).sin_cos();
+        Self {
+            w: cos,
+            x: axis.x * sin,
+            y: axis.y * sin,
+            z: axis.z * sin,
+        }
+    }
+
+    #[inline(always)]
+    pub fn dot(a: Quat, b: Quat) -> f64 {
+        a.w * b.w + a.x * b.x + a.y * b.y + a.z * b.z
+    }
+
+    pub fn length(&self) -> f64 {
+        Self::dot(*self, *self).sqrt()
+    }
+
+    pub fn normalize(q: Quat) -> Self {
+        let length = q.length();
+        Self {
+            w: q.w / length,
+            x: q.x / length,
+            y: q.y / length,
+            z: q.z / length,
+        }
+    }
+
+    /// Spherical linear interpolation along the shorter arc from `a` to `b`.
+    pub fn slerp(a: Quat, b: Quat, t: f64) -> Self {
+        let mut cos_theta = Self::dot(a, b);
+        let b = if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            Quat::from(-b.w, -b.x, -b.y, -b.z)
+        } else {
+            b
+        };
+
+        let (wa, wb) = if cos_theta > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (((1.0 - t) * theta).sin() / sin_theta, (t * theta).sin() / sin_theta)
+        };
+
+        Self::normalize(Quat::from(
+            wa * a.w + wb * b.w,
+            wa * a.x + wb * b.x,
+            wa * a.y + wb * b.y,
+            wa * a.z + wb * b.z,
+        ))
+    }
+
+    pub fn to_mat4(self) -> Mat4 {
+        let Self { w, x, y, z } = self;
+        Mat4::from([
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y), 0.0],
+            [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x), 0.0],
+            [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul<Quat> for Quat {
+    type Output = Quat;
+
+    fn mul(self, rhs: Quat) -> Self::Output {
+        Self::Output {
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// q and -q describe the same rotation.
+    fn same_rotation(a: Quat, b: Quat) -> bool {
+        1.0 - Quat::dot(a, b).abs() < 1e-12
+    }
+
+    #[test]
+    fn slerp_hits_its_endpoints() {
+        let a = Quat::from_axis_angle((0.0, 1.0, 0.0), 30.0);
+        let b = Quat::from_axis_angle((1.0, 0.0, 1.0), 120.0);
+        assert!(same_rotation(Quat::slerp(a, b, 0.0), a));
+        assert!(same_rotation(Quat::slerp(a, b, 1.0), b));
+    }
+
+    #[test]
+    fn slerp_takes_the_shorter_arc() {
+        let a = Quat::identity();
+        let b = Quat::from_axis_angle((0.0, 0.0, 1.0), 90.0);
+        let flipped = Quat::from(-b.w, -b.x, -b.y, -b.z);
+        assert!(same_rotation(Quat::slerp(a, flipped, 0.5), Quat::from_axis_angle((0.0, 0.0, 1.0), 45.0)));
+    }
+
+    #[test]
+    fn slerp_of_nearly_equal_rotations_stays_unit() {
+        let a = Quat::from_axis_angle((0.0, 1.0, 0.0), 10.0);
+        let b = Quat::from_axis_angle((0.0, 1.0, 0.0), 10.01);
+        assert!((Quat::slerp(a, b, 0.5).length() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn matrix_matches_mat4_rotation() {
+        let q = Quat::from_axis_angle((1.0, 2.0, 3.0), 70.0);
+        let m = Mat4::rotation((1.0, 2.0, 3.0), 70.0);
+        for (row_q, row_m) in q.to_mat4().m.iter().zip(&m.m) {
+            for (x, y) in row_q.iter().zip(row_m) {
+                assert!((x - y).abs() < 1e-12);
+            }
+        }
+    }
+}
@@ -178,6 +178,14 @@ impl Vec3 {
         }
     }
 
+    /// Two unit vectors that with the unit vector `w` form a right-handed
+    /// orthonormal basis.
+    pub fn orthonormal_basis(w: Self) -> (Self, Self) {
+        let helper = if w.x.abs() > 0.9 { Self::unit_y() } else { Self::unit_x() };
+        let tangent = Self::unit_vector(Self::cross(helper, w));
+        (tangent, Self::cross(w, tangent))
+    }
+
     #[inline(always)]
     pub fn reflect(v: Self, n: Self) -> Self {
         v - 2.0 * Self::dot(v,n) * n